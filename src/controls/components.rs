@@ -0,0 +1,18 @@
+use bevy::prelude::*;
+
+use super::resources::Action;
+
+
+#[derive(Component)]
+pub struct ControlsMenu;
+
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+pub enum ControlsButton {
+    Rebind(Action),
+    StickDeadzone,
+    ResetDefaults,
+    Back,
+}
+
+#[derive(Component)]
+pub struct ControlsLabel(pub ControlsButton);
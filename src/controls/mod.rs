@@ -0,0 +1,53 @@
+use bevy::input::InputSystem;
+use bevy::prelude::*;
+
+pub mod components;
+pub mod resources;
+mod storage;
+mod systems;
+
+use crate::game::SimulationState;
+use crate::settings::SettingsMenuState;
+use crate::AppState;
+
+use self::{resources::*, systems::*};
+
+pub const CONTROLS_FILE: &str = "controls.ron";
+pub const DEADZONE_STEP: f32 = 0.05;
+pub const MAX_DEADZONE: f32 = 0.5;
+/// How far the virtual joystick's knob can be dragged from its centre.
+pub const JOYSTICK_RADIUS: f32 = 80.0;
+
+pub struct ControlsPlugin;
+
+impl Plugin for ControlsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<InputBindings>()
+            .init_resource::<ActionState>()
+            .init_resource::<PointerInput>()
+            .add_systems(Startup, load_bindings_on_startup)
+            .add_systems(
+                PreUpdate,
+                (update_action_state, update_pointer_input).after(InputSystem),
+            )
+            .add_systems(
+                Update,
+                draw_virtual_joystick
+                    .run_if(in_state(AppState::Game))
+                    .run_if(in_state(SimulationState::Running))
+                    .run_if(virtual_joystick_enabled),
+            )
+            .add_systems(OnEnter(SettingsMenuState::Controls), spawn_controls_menu)
+            .add_systems(
+                Update,
+                (
+                    interact_with_controls_buttons,
+                    capture_rebinding.run_if(resource_exists::<Rebinding>()),
+                    update_controls_labels,
+                )
+                    .chain()
+                    .run_if(in_state(SettingsMenuState::Controls)),
+            )
+            .add_systems(OnExit(SettingsMenuState::Controls), (cancel_rebinding, save_bindings));
+    }
+}
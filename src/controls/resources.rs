@@ -0,0 +1,279 @@
+use std::collections::{BTreeMap, HashSet};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::JOYSTICK_RADIUS;
+
+
+/// Something the player can do, independent of the key or button it is bound to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Action {
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    Dash,
+    Pause,
+    Restart,
+    Confirm,
+    Back,
+    MenuUp,
+    MenuDown,
+    MenuLeft,
+    MenuRight,
+    MenuNext,
+    /// Removes the last letter of a name being typed.
+    Erase,
+    /// Finishes typing a name without going through the on-screen keyboard.
+    Submit,
+    DebugGame,
+    DebugMainMenu,
+}
+
+impl Action {
+    /// Actions listed on the controls screen. The rest can still be changed in the file.
+    pub const REBINDABLE: [Action; 9] = [
+        Action::MoveUp,
+        Action::MoveDown,
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::Dash,
+        Action::Pause,
+        Action::Restart,
+        Action::Confirm,
+        Action::Back,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Action::MoveUp => "Move Up",
+            Action::MoveDown => "Move Down",
+            Action::MoveLeft => "Move Left",
+            Action::MoveRight => "Move Right",
+            Action::Dash => "Dash",
+            Action::Pause => "Pause",
+            Action::Restart => "Restart",
+            Action::Confirm => "Confirm",
+            Action::Back => "Back",
+            Action::MenuUp => "Menu Up",
+            Action::MenuDown => "Menu Down",
+            Action::MenuLeft => "Menu Left",
+            Action::MenuRight => "Menu Right",
+            Action::MenuNext => "Menu Next",
+            Action::Erase => "Erase",
+            Action::Submit => "Submit",
+            Action::DebugGame => "Debug: Game",
+            Action::DebugMainMenu => "Debug: Main Menu",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InputBinding {
+    Key(KeyCode),
+    /// A button on any connected gamepad.
+    Gamepad(GamepadButtonType),
+}
+
+impl InputBinding {
+    pub fn label(self) -> String {
+        match self {
+            InputBinding::Key(key) => format!("{:?}", key),
+            InputBinding::Gamepad(button) => format!("Pad {:?}", button),
+        }
+    }
+
+    fn same_device(self, other: InputBinding) -> bool {
+        matches!(
+            (self, other),
+            (InputBinding::Key(_), InputBinding::Key(_))
+                | (InputBinding::Gamepad(_), InputBinding::Gamepad(_))
+        )
+    }
+}
+
+
+/// Which inputs trigger each action, saved to `CONTROLS_FILE`.
+#[derive(Resource, Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InputBindings {
+    pub actions: BTreeMap<Action, Vec<InputBinding>>,
+    /// Left stick deflection below this is ignored, and the rest rescaled to start from zero.
+    pub stick_deadzone: f32,
+}
+
+impl Default for InputBindings {
+    fn default() -> Self {
+        use GamepadButtonType as Pad;
+        use InputBinding::{Gamepad, Key};
+
+        let actions = [
+            (Action::MoveUp, vec![Key(KeyCode::W), Key(KeyCode::Up), Gamepad(Pad::DPadUp)]),
+            (Action::MoveDown, vec![Key(KeyCode::S), Key(KeyCode::Down), Gamepad(Pad::DPadDown)]),
+            (Action::MoveLeft, vec![Key(KeyCode::A), Key(KeyCode::Left), Gamepad(Pad::DPadLeft)]),
+            (Action::MoveRight, vec![Key(KeyCode::D), Key(KeyCode::Right), Gamepad(Pad::DPadRight)]),
+            (Action::Dash, vec![Key(KeyCode::ShiftLeft), Gamepad(Pad::West)]),
+            (Action::Pause, vec![Key(KeyCode::Space), Gamepad(Pad::Start)]),
+            (Action::Restart, vec![Key(KeyCode::R), Gamepad(Pad::Select)]),
+            (Action::Confirm, vec![Key(KeyCode::Return), Gamepad(Pad::South)]),
+            (Action::Back, vec![Key(KeyCode::Escape), Gamepad(Pad::East)]),
+            (Action::MenuUp, vec![Key(KeyCode::Up), Gamepad(Pad::DPadUp)]),
+            (Action::MenuDown, vec![Key(KeyCode::Down), Gamepad(Pad::DPadDown)]),
+            (Action::MenuLeft, vec![Key(KeyCode::Left), Gamepad(Pad::DPadLeft)]),
+            (Action::MenuRight, vec![Key(KeyCode::Right), Gamepad(Pad::DPadRight)]),
+            (Action::MenuNext, vec![Key(KeyCode::Tab)]),
+            (Action::Erase, vec![Key(KeyCode::Back), Gamepad(Pad::East)]),
+            (Action::Submit, vec![Gamepad(Pad::Start)]),
+            (Action::DebugGame, vec![Key(KeyCode::G)]),
+            (Action::DebugMainMenu, vec![Key(KeyCode::M)]),
+        ];
+
+        Self {
+            actions: actions.into_iter().collect(),
+            stick_deadzone: 0.2,
+        }
+    }
+}
+
+impl InputBindings {
+    pub fn bindings(&self, action: Action) -> &[InputBinding] {
+        self.actions.get(&action).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// Replaces the action's first binding on the same device, or adds one if it has none.
+    /// If another rebindable action already uses `binding`, the two swap: it gets the binding
+    /// that was replaced, or loses `binding` if nothing was. Returns the action swapped with.
+    pub fn rebind(&mut self, action: Action, binding: InputBinding) -> Option<Action> {
+        let bindings = self.actions.entry(action).or_default();
+        let replaced = match bindings.iter().position(|existing| existing.same_device(binding)) {
+            Some(index) => Some(std::mem::replace(&mut bindings[index], binding)),
+            None => {
+                bindings.push(binding);
+                None
+            }
+        };
+        bindings.dedup();
+
+        let conflict = Action::REBINDABLE
+            .into_iter()
+            .find(|other| *other != action && self.bindings(*other).contains(&binding))?;
+        let other_bindings = self.actions.entry(conflict).or_default();
+        other_bindings.retain(|existing| *existing != binding);
+        if let Some(replaced) = replaced.filter(|replaced| !other_bindings.contains(replaced)) {
+            other_bindings.push(replaced);
+        }
+        Some(conflict)
+    }
+
+    /// Gives actions the file doesn't mention their default bindings, e.g. after an update adds one.
+    pub fn fill_missing(&mut self) {
+        for (action, bindings) in InputBindings::default().actions {
+            self.actions.entry(action).or_insert(bindings);
+        }
+    }
+}
+
+
+/// Where a player's input comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputSource {
+    /// The keyboard and every gamepad at once, for a single player.
+    Any,
+    Keyboard,
+    /// The n-th connected gamepad, counting from 0 in connection order.
+    Gamepad(usize),
+}
+
+/// Actions read from one device, or from all of them together.
+#[derive(Default)]
+pub struct DeviceActions {
+    pub(super) pressed: HashSet<Action>,
+    pub(super) just_pressed: HashSet<Action>,
+    pub(super) move_axis: Vec2,
+}
+
+impl DeviceActions {
+    pub fn pressed(&self, action: Action) -> bool {
+        self.pressed.contains(&action)
+    }
+
+    pub fn just_pressed(&self, action: Action) -> bool {
+        self.just_pressed.contains(&action)
+    }
+
+    /// Movement from the move actions and the left stick, at most 1 long.
+    pub fn move_axis(&self) -> Vec2 {
+        self.move_axis
+    }
+}
+
+/// This frame's actions, per device and merged from every keyboard and gamepad binding.
+/// Menus read the merged actions; players read the device they are assigned.
+#[derive(Resource, Default)]
+pub struct ActionState {
+    pub(super) any: DeviceActions,
+    pub(super) keyboard: DeviceActions,
+    pub(super) gamepads: Vec<DeviceActions>,
+}
+
+impl ActionState {
+    pub fn pressed(&self, action: Action) -> bool {
+        self.any.pressed(action)
+    }
+
+    pub fn just_pressed(&self, action: Action) -> bool {
+        self.any.just_pressed(action)
+    }
+
+    pub fn move_axis(&self) -> Vec2 {
+        self.any.move_axis()
+    }
+
+    /// Actions of `source`, or `None` for a gamepad that isn't connected.
+    pub fn source(&self, source: InputSource) -> Option<&DeviceActions> {
+        match source {
+            InputSource::Any => Some(&self.any),
+            InputSource::Keyboard => Some(&self.keyboard),
+            InputSource::Gamepad(index) => self.gamepads.get(index),
+        }
+    }
+
+    pub fn just_pressed_by(&self, source: InputSource, action: Action) -> bool {
+        self.source(source)
+            .is_some_and(|actions| actions.just_pressed(action))
+    }
+
+    pub fn move_axis_of(&self, source: InputSource) -> Vec2 {
+        self.source(source)
+            .map(DeviceActions::move_axis)
+            .unwrap_or_default()
+    }
+}
+
+/// Cursor and touch input for the pointer control schemes, in world coordinates.
+#[derive(Resource, Default)]
+pub struct PointerInput {
+    /// The first finger on the screen, or else the cursor while it is over the window.
+    pub position: Option<Vec2>,
+    /// Where the current touch or left click started, the centre of the virtual joystick.
+    pub press_origin: Option<Vec2>,
+}
+
+impl PointerInput {
+    /// How far the press has been dragged from where it started, at most 1 long.
+    pub fn joystick_axis(&self) -> Vec2 {
+        match (self.press_origin, self.position) {
+            (Some(origin), Some(position)) => {
+                ((position - origin) / JOYSTICK_RADIUS).clamp_length_max(1.0)
+            }
+            _ => Vec2::ZERO,
+        }
+    }
+}
+
+/// The controls screen is waiting for the next key or button to bind to `action`.
+#[derive(Resource)]
+pub struct Rebinding {
+    pub action: Action,
+}
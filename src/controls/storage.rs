@@ -0,0 +1,57 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use bevy::prelude::*;
+use ron::ser::PrettyConfig;
+
+use crate::persistence::{back_up, data_dir, write_atomically};
+
+use super::resources::InputBindings;
+use super::CONTROLS_FILE;
+
+pub fn bindings_path() -> PathBuf {
+    data_dir().join(CONTROLS_FILE)
+}
+
+/// Reads the bindings at `path`, or the defaults if there is no file or it can't be parsed.
+pub fn load_bindings(path: &Path) -> InputBindings {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return InputBindings::default(),
+        Err(error) => {
+            warn!("Could not read controls from {}: {}", path.display(), error);
+            return InputBindings::default();
+        }
+    };
+
+    match ron::from_str::<InputBindings>(&contents) {
+        Ok(mut bindings) => {
+            bindings.fill_missing();
+            bindings
+        }
+        Err(error) => {
+            match back_up(path) {
+                Ok(backup_path) => warn!(
+                    "Controls in {} are unreadable ({}), moved to {}",
+                    path.display(),
+                    error,
+                    backup_path.display()
+                ),
+                Err(backup_error) => warn!(
+                    "Controls in {} are unreadable ({}) and could not be backed up: {}",
+                    path.display(),
+                    error,
+                    backup_error
+                ),
+            }
+            InputBindings::default()
+        }
+    }
+}
+
+pub fn write_bindings(bindings: &InputBindings, path: &Path) -> io::Result<()> {
+    let contents = ron::ser::to_string_pretty(bindings, PrettyConfig::default())
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+    write_atomically(path, &contents)
+}
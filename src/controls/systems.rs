@@ -0,0 +1,360 @@
+use bevy::prelude::*;
+use bevy::ui::FocusPolicy;
+use bevy::window::PrimaryWindow;
+
+use crate::events::ButtonActivated;
+use crate::settings::resources::{ControlScheme, Settings};
+use crate::settings::SettingsMenuState;
+use crate::state_scoped::StateScoped;
+use crate::ui::components::*;
+use crate::ui::styles::*;
+use crate::ui::SETTINGS_LAYER;
+
+use super::components::*;
+use super::resources::*;
+use super::storage::*;
+use super::{DEADZONE_STEP, JOYSTICK_RADIUS, MAX_DEADZONE};
+
+
+pub fn load_bindings_on_startup(mut commands: Commands) {
+    commands.insert_resource(load_bindings(&bindings_path()));
+}
+
+pub fn save_bindings(bindings: Res<InputBindings>) {
+    let path = bindings_path();
+    if let Err(error) = write_bindings(&bindings, &path) {
+        error!("Could not save controls to {}: {}", path.display(), error);
+    }
+}
+
+pub fn update_action_state(
+    keyboard_input: Res<Input<KeyCode>>,
+    gamepad_input: Res<Input<GamepadButton>>,
+    gamepad_axes: Res<Axis<GamepadAxis>>,
+    gamepads: Res<Gamepads>,
+    bindings: Res<InputBindings>,
+    rebinding: Option<Res<Rebinding>>,
+    mut action_state: ResMut<ActionState>,
+) {
+    *action_state = ActionState::default();
+
+    // The next key press is meant for the controls screen, not for whatever it is bound to.
+    if rebinding.is_some() {
+        return;
+    }
+
+    action_state.keyboard = read_device(&bindings, Vec2::ZERO, |binding| match binding {
+        InputBinding::Key(key) => (keyboard_input.pressed(key), keyboard_input.just_pressed(key)),
+        InputBinding::Gamepad(_) => (false, false),
+    });
+
+    // Gamepad ids only grow as pads connect, so sorting by them keeps the connection order.
+    let mut connected: Vec<Gamepad> = gamepads.iter().collect();
+    connected.sort_by_key(|gamepad| gamepad.id);
+
+    action_state.gamepads = connected
+        .into_iter()
+        .map(|gamepad| {
+            let stick = Vec2::new(
+                gamepad_axes
+                    .get(GamepadAxis::new(gamepad, GamepadAxisType::LeftStickX))
+                    .unwrap_or_default(),
+                gamepad_axes
+                    .get(GamepadAxis::new(gamepad, GamepadAxisType::LeftStickY))
+                    .unwrap_or_default(),
+            );
+
+            read_device(&bindings, stick, |binding| match binding {
+                InputBinding::Key(_) => (false, false),
+                InputBinding::Gamepad(button_type) => {
+                    let button = GamepadButton::new(gamepad, button_type);
+                    (gamepad_input.pressed(button), gamepad_input.just_pressed(button))
+                }
+            })
+        })
+        .collect();
+
+    let mut any = DeviceActions::default();
+    for device in std::iter::once(&action_state.keyboard).chain(action_state.gamepads.iter()) {
+        any.pressed.extend(device.pressed.iter().copied());
+        any.just_pressed.extend(device.just_pressed.iter().copied());
+        any.move_axis += device.move_axis;
+    }
+    any.move_axis = any.move_axis.clamp_length_max(1.0);
+    action_state.any = any;
+}
+
+/// Collects the actions whose bindings `read` reports as held, plus movement from the move
+/// actions and `stick`. `read` returns whether a binding is pressed and just pressed.
+fn read_device(
+    bindings: &InputBindings,
+    stick: Vec2,
+    read: impl Fn(InputBinding) -> (bool, bool),
+) -> DeviceActions {
+    let mut actions = DeviceActions::default();
+
+    for (action, action_bindings) in bindings.actions.iter() {
+        for binding in action_bindings {
+            let (pressed, just_pressed) = read(*binding);
+            if pressed {
+                actions.pressed.insert(*action);
+            }
+            if just_pressed {
+                actions.just_pressed.insert(*action);
+            }
+        }
+    }
+
+    let mut digital = Vec2::ZERO;
+    for (action, direction) in [
+        (Action::MoveUp, Vec2::Y),
+        (Action::MoveDown, Vec2::NEG_Y),
+        (Action::MoveLeft, Vec2::NEG_X),
+        (Action::MoveRight, Vec2::X),
+    ] {
+        if actions.pressed(action) {
+            digital += direction;
+        }
+    }
+
+    let analog = apply_deadzone(stick, bindings.stick_deadzone);
+    actions.move_axis = (digital.normalize_or_zero() + analog).clamp_length_max(1.0);
+    actions
+}
+
+pub fn update_pointer_input(
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    mouse_input: Res<Input<MouseButton>>,
+    touches: Res<Touches>,
+    mut pointer_input: ResMut<PointerInput>,
+) {
+    let Ok(window) = window_query.get_single() else {
+        return;
+    };
+    // Window coordinates start at the top left, the world's at the bottom left.
+    let to_world = |position: Vec2| Vec2::new(position.x, window.height() - position.y);
+
+    if let Some(touch) = touches.iter().next() {
+        pointer_input.position = Some(to_world(touch.position()));
+        pointer_input.press_origin = Some(to_world(touch.start_position()));
+        return;
+    }
+
+    pointer_input.position = window.cursor_position().map(to_world);
+    if !mouse_input.pressed(MouseButton::Left) {
+        pointer_input.press_origin = None;
+    } else if mouse_input.just_pressed(MouseButton::Left) || pointer_input.press_origin.is_none() {
+        pointer_input.press_origin = pointer_input.position;
+    }
+}
+
+pub fn virtual_joystick_enabled(settings: Res<Settings>) -> bool {
+    settings.control_scheme == ControlScheme::VirtualJoystick
+}
+
+pub fn draw_virtual_joystick(mut gizmos: Gizmos, pointer_input: Res<PointerInput>) {
+    let Some(origin) = pointer_input.press_origin else {
+        return;
+    };
+
+    let knob = origin + pointer_input.joystick_axis() * JOYSTICK_RADIUS;
+    gizmos.circle_2d(origin, JOYSTICK_RADIUS, Color::rgba(1.0, 1.0, 1.0, 0.4));
+    gizmos.circle_2d(knob, JOYSTICK_RADIUS / 3.0, Color::WHITE);
+}
+
+/// Radial deadzone that rescales what is left, so movement starts from zero at its edge.
+fn apply_deadzone(stick: Vec2, deadzone: f32) -> Vec2 {
+    let length = stick.length();
+    if length <= deadzone || deadzone >= 1.0 {
+        return Vec2::ZERO;
+    }
+
+    stick / length * ((length - deadzone) / (1.0 - deadzone)).min(1.0)
+}
+
+pub fn spawn_controls_menu(mut commands: Commands, bindings: Res<InputBindings>) {
+    let buttons: Vec<ControlsButton> = Action::REBINDABLE
+        .into_iter()
+        .map(ControlsButton::Rebind)
+        .chain([
+            ControlsButton::StickDeadzone,
+            ControlsButton::ResetDefaults,
+            ControlsButton::Back,
+        ])
+        .collect();
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    row_gap: Val::Px(6.0),
+                    ..screen_style()
+                },
+                background_color: OVERLAY_COLOR.into(),
+                focus_policy: FocusPolicy::Block,
+                z_index: ZIndex::Global(SETTINGS_LAYER as i32),
+                ..default()
+            },
+            FocusGroup {
+                columns: 1,
+                layer: SETTINGS_LAYER,
+            },
+            ControlsMenu,
+            StateScoped(SettingsMenuState::Controls),
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section("Controls", title_text_style()));
+
+            for (index, button) in buttons.into_iter().enumerate() {
+                let mut button_entity = parent.spawn((
+                    ButtonBundle {
+                        style: Style {
+                            width: Val::Px(520.0),
+                            ..list_button_style()
+                        },
+                        background_color: NORMAL_BUTTON_COLOR.into(),
+                        ..default()
+                    },
+                    Focusable { index },
+                    button,
+                ));
+                button_entity.with_children(|parent| {
+                    parent.spawn((
+                        TextBundle::from_section(
+                            controls_label(button, &bindings, None),
+                            list_button_text_style(),
+                        ),
+                        ControlsLabel(button),
+                    ));
+                });
+
+                if button == ControlsButton::Back {
+                    button_entity.insert(CancelButton);
+                }
+            }
+        });
+}
+
+pub fn interact_with_controls_buttons(
+    mut commands: Commands,
+    mut button_activated_event_reader: EventReader<ButtonActivated>,
+    button_query: Query<&ControlsButton>,
+    mut bindings: ResMut<InputBindings>,
+) {
+    for event in button_activated_event_reader.read() {
+        match button_query.get(event.entity) {
+            Ok(ControlsButton::Rebind(action)) => {
+                commands.insert_resource(Rebinding { action: *action });
+            }
+            Ok(ControlsButton::StickDeadzone) => {
+                let deadzone = bindings.stick_deadzone + DEADZONE_STEP;
+                bindings.stick_deadzone = if deadzone > MAX_DEADZONE + f32::EPSILON {
+                    0.0
+                } else {
+                    deadzone
+                };
+            }
+            Ok(ControlsButton::ResetDefaults) => {
+                *bindings = InputBindings::default();
+            }
+            Ok(ControlsButton::Back) => {
+                commands.insert_resource(NextState(Some(SettingsMenuState::Open)));
+            }
+            Err(_) => {}
+        }
+    }
+}
+
+/// Binds the first key or gamepad button pressed after picking an action.
+/// Escape, or anything bound to Back, cancels instead.
+pub fn capture_rebinding(
+    mut commands: Commands,
+    keyboard_input: Res<Input<KeyCode>>,
+    gamepad_input: Res<Input<GamepadButton>>,
+    rebinding: Res<Rebinding>,
+    mut bindings: ResMut<InputBindings>,
+) {
+    // Skip the press that picked the action in the first place.
+    if rebinding.is_added() {
+        return;
+    }
+
+    let binding = keyboard_input
+        .get_just_pressed()
+        .next()
+        .map(|key| InputBinding::Key(*key))
+        .or_else(|| {
+            gamepad_input
+                .get_just_pressed()
+                .next()
+                .map(|button| InputBinding::Gamepad(button.button_type))
+        });
+
+    let Some(binding) = binding else {
+        return;
+    };
+    commands.remove_resource::<Rebinding>();
+
+    let cancelled = binding == InputBinding::Key(KeyCode::Escape)
+        || bindings.bindings(Action::Back).contains(&binding);
+    if cancelled {
+        return;
+    }
+
+    if let Some(swapped) = bindings.rebind(rebinding.action, binding) {
+        warn!(
+            "{} was already bound to {}, which now takes {}'s old binding",
+            binding.label(),
+            swapped.label(),
+            rebinding.action.label()
+        );
+    }
+}
+
+pub fn cancel_rebinding(mut commands: Commands) {
+    commands.remove_resource::<Rebinding>();
+}
+
+pub fn update_controls_labels(
+    bindings: Res<InputBindings>,
+    rebinding: Option<Res<Rebinding>>,
+    mut last_waiting_for: Local<Option<Action>>,
+    mut label_query: Query<(&mut Text, &ControlsLabel)>,
+) {
+    // Compared with last frame so a cancelled rebind, which leaves the bindings alone, still shows.
+    let waiting_for = rebinding.map(|rebinding| rebinding.action);
+    let waiting_changed = waiting_for != *last_waiting_for;
+    *last_waiting_for = waiting_for;
+    if !bindings.is_changed() && !waiting_changed {
+        return;
+    }
+
+    for (mut text, label) in label_query.iter_mut() {
+        text.sections[0].value = controls_label(label.0, &bindings, waiting_for);
+    }
+}
+
+fn controls_label(button: ControlsButton, bindings: &InputBindings, waiting_for: Option<Action>) -> String {
+    match button {
+        ControlsButton::Rebind(action) if waiting_for == Some(action) => {
+            format!("{}: press a key or button (Esc cancels)...", action.label())
+        }
+        ControlsButton::Rebind(action) => {
+            let bound: Vec<String> = bindings
+                .bindings(action)
+                .iter()
+                .map(|binding| binding.label())
+                .collect();
+            if bound.is_empty() {
+                format!("{}: unbound", action.label())
+            } else {
+                format!("{}: {}", action.label(), bound.join(", "))
+            }
+        }
+        ControlsButton::StickDeadzone => {
+            format!("Stick Deadzone: {}%", (bindings.stick_deadzone * 100.0).round())
+        }
+        ControlsButton::ResetDefaults => "Reset to Defaults".to_string(),
+        ControlsButton::Back => "Back".to_string(),
+    }
+}
@@ -1,6 +1,103 @@
+use std::time::Duration;
+
 use bevy::prelude::*;
 
+use crate::game::powerup::components::PowerUpKind;
+use crate::game::star::components::StarKind;
+use crate::game::stats::resources::RunStats;
+
+/// Sent once, when the last player still standing goes down.
 #[derive(Event)]
 pub struct GameOver {
     pub score: u32,
-}
\ No newline at end of file
+    /// One entry per player, in player order.
+    pub players: Vec<PlayerResult>,
+    pub difficulty_level: u32,
+    pub stats: RunStats,
+}
+
+/// How one player did over a run.
+#[derive(Debug, Clone)]
+pub struct PlayerResult {
+    pub player: usize,
+    pub score: u32,
+    pub time_survived: Duration,
+}
+
+/// Throws away the current run and starts a fresh one without leaving `AppState::Game`.
+#[derive(Event)]
+pub struct RestartRun;
+
+/// The player started a dash. Hook for sound and effects.
+#[derive(Event)]
+pub struct PlayerDashed {
+    pub player: Entity,
+}
+
+/// A player picked up a star at `position`.
+#[derive(Event)]
+pub struct StarCollected {
+    pub player: usize,
+    pub kind: StarKind,
+    pub position: Vec2,
+}
+
+/// A boss ran out of health. `boss` counts from 1 over a run.
+#[derive(Event)]
+pub struct BossDefeated {
+    pub boss: usize,
+    /// Index of the player who landed the final hit, credited with `bonus`.
+    pub player: usize,
+    pub bonus: u32,
+}
+
+/// Two enemies bumped into each other. Hook for sound and effects.
+#[derive(Event)]
+pub struct EnemyCollided {
+    pub enemies: [Entity; 2],
+    /// Where they touched.
+    pub position: Vec2,
+    /// How fast they were closing in on each other.
+    pub impact: f32,
+}
+
+/// A player picked up a power-up. Bombs go off right away; other kinds start or stack an effect.
+#[derive(Event)]
+pub struct PowerUpCollected {
+    pub player: usize,
+    pub kind: PowerUpKind,
+}
+
+/// A power-up effect ran out, or a shield was used up.
+#[derive(Event)]
+pub struct PowerUpExpired {
+    pub player: usize,
+    pub kind: PowerUpKind,
+}
+
+/// A wave's first group is about to appear. `wave` counts from 1.
+#[derive(Event)]
+pub struct WaveStarted {
+    pub wave: usize,
+    /// Generated rather than authored.
+    pub endless: bool,
+}
+
+/// A wave met its completion condition; the next one is on its way.
+#[derive(Event)]
+pub struct WaveCleared {
+    pub wave: usize,
+}
+
+/// A qualifying score the player has put a name to.
+#[derive(Event)]
+pub struct HighScoreEntered {
+    pub name: String,
+    pub score: u32,
+}
+
+/// A menu button was clicked, or confirmed while focused.
+#[derive(Event)]
+pub struct ButtonActivated {
+    pub entity: Entity,
+}
@@ -0,0 +1,52 @@
+use bevy::prelude::*;
+
+
+/// The boss also carries an `Enemy`, which moves it and lets it hit players like any other enemy.
+#[derive(Component)]
+pub struct Boss {
+    /// Counts from 1 over a run.
+    pub number: usize,
+    pub health: u32,
+    pub max_health: u32,
+    pub phase: BossPhase,
+    /// Index of the player who landed the latest hit, credited with the win.
+    pub last_hit_by: Option<usize>,
+}
+
+/// Paces the boss's charges and barrages. Kept apart from `Boss` so ticking it doesn't mark the
+/// boss changed every frame.
+#[derive(Component)]
+pub struct BossAttack {
+    pub timer: Timer,
+}
+
+/// What the boss is up to. It moves through these in order as it loses health.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BossPhase {
+    /// Bounces around like a slow bouncer.
+    Drift,
+    /// Lunges at the nearest player every so often.
+    Charge,
+    /// Speeds up and keeps throwing out rings of darts.
+    Barrage,
+}
+
+impl BossPhase {
+    pub fn for_health(health: u32, max_health: u32) -> Self {
+        let fraction = health as f32 / max_health.max(1) as f32;
+
+        if fraction > 2.0 / 3.0 {
+            BossPhase::Drift
+        } else if fraction > 1.0 / 3.0 {
+            BossPhase::Charge
+        } else {
+            BossPhase::Barrage
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct BossHealthBar;
+
+#[derive(Component)]
+pub struct BossHealthFill;
@@ -0,0 +1,60 @@
+use bevy::prelude::*;
+
+pub mod components;
+pub mod resources;
+mod systems;
+
+use crate::events::{BossDefeated, StarCollected};
+use crate::AppState;
+
+use self::{resources::*, systems::*};
+
+use super::{RunRestartSet, SimulationState};
+
+pub const BOSS_SIZE: f32 = 160.0;
+pub const BOSS_SPEED: f32 = 110.0;
+pub const BOSS_TINT: Color = Color::rgb(0.7, 0.3, 1.0);
+/// How long the boss's warning marker shows, a bit longer than a regular enemy's.
+pub const BOSS_TELEGRAPH_TIME: f32 = 2.5;
+/// Health of the first boss; each one after it gets `BOSS_HEALTH_STEP` more.
+pub const BOSS_HEALTH: u32 = 8;
+pub const BOSS_HEALTH_STEP: u32 = 4;
+/// Stars collected within this distance of the boss hurt it.
+pub const BOSS_DAMAGE_RADIUS: f32 = 250.0;
+pub const BOSS_STAR_DAMAGE: u32 = 1;
+/// Points for beating the first boss; each one after it is worth this much more again.
+pub const BOSS_BONUS: u32 = 25;
+pub const BOSS_CHARGE_INTERVAL: f32 = 2.5;
+pub const BOSS_CHARGE_SPEEDUP: f32 = 2.2;
+pub const BOSS_BARRAGE_INTERVAL: f32 = 4.0;
+pub const BOSS_BARRAGE_COUNT: usize = 6;
+pub const BOSS_BARRAGE_SPEEDUP: f32 = 1.5;
+pub const BOSS_BAR_WIDTH: f32 = 320.0;
+pub const BOSS_BAR_HEIGHT: f32 = 16.0;
+
+pub struct BossPlugin;
+
+impl Plugin for BossPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<BossSchedule>()
+            .add_event::<StarCollected>()
+            .add_event::<BossDefeated>()
+            .add_systems(OnEnter(AppState::Game), reset_boss_schedule)
+            .add_systems(Update, reset_boss_schedule.in_set(RunRestartSet::Setup))
+            .add_systems(
+                Update,
+                (
+                    spawn_boss_when_due,
+                    set_up_boss,
+                    damage_boss,
+                    run_boss_phases,
+                    defeat_boss,
+                    award_boss_bonus,
+                    update_boss_health_bar,
+                )
+                    .chain()
+                    .run_if(in_state(AppState::Game))
+                    .run_if(in_state(SimulationState::Running)),
+            );
+    }
+}
@@ -0,0 +1,44 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+
+/// What brings on a boss: the team score or the run time reaching a mark.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BossTrigger {
+    Score(u32),
+    /// Seconds into the run.
+    Time(f32),
+}
+
+/// Bosses of a run, in order. Only one is out at a time; the next waits for it to go down.
+#[derive(Resource, Debug)]
+pub struct BossSchedule {
+    pub triggers: Vec<BossTrigger>,
+    /// Index of the next trigger, which is also how many bosses have appeared.
+    pub next: usize,
+}
+
+impl Default for BossSchedule {
+    fn default() -> Self {
+        Self {
+            triggers: vec![
+                BossTrigger::Score(25),
+                BossTrigger::Time(180.0),
+                BossTrigger::Score(100),
+                BossTrigger::Time(420.0),
+            ],
+            next: 0,
+        }
+    }
+}
+
+impl BossSchedule {
+    pub fn is_due(&self, score: u32, elapsed: Duration) -> bool {
+        match self.triggers.get(self.next) {
+            Some(BossTrigger::Score(points)) => score >= *points,
+            Some(BossTrigger::Time(seconds)) => elapsed.as_secs_f32() >= *seconds,
+            None => false,
+        }
+    }
+}
@@ -0,0 +1,268 @@
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+use rand::prelude::*;
+
+use crate::events::{BossDefeated, StarCollected};
+use crate::game::enemy::components::{Enemy, EnemyKind, SpawnWarning};
+use crate::game::enemy::resources::EnemySpawnConfig;
+use crate::game::difficulty::resources::DifficultyDirector;
+use crate::game::enemy::systems::{count_regular_enemies, kind_of, spawn_warning};
+use crate::game::player::components::Player;
+use crate::game::resources::RunTime;
+use crate::game::score::resources::Score;
+use crate::state_scoped::StateScoped;
+use crate::ui::styles::body_text_style;
+use crate::AppState;
+
+use super::components::*;
+use super::resources::*;
+use super::{
+    BOSS_BARRAGE_COUNT, BOSS_BARRAGE_INTERVAL, BOSS_BARRAGE_SPEEDUP, BOSS_BAR_HEIGHT, BOSS_BAR_WIDTH, BOSS_BONUS,
+    BOSS_CHARGE_INTERVAL, BOSS_CHARGE_SPEEDUP, BOSS_DAMAGE_RADIUS, BOSS_HEALTH, BOSS_HEALTH_STEP, BOSS_SIZE, BOSS_SPEED,
+    BOSS_STAR_DAMAGE, BOSS_TELEGRAPH_TIME, BOSS_TINT,
+};
+
+
+pub fn reset_boss_schedule(mut boss_schedule: ResMut<BossSchedule>) {
+    boss_schedule.next = 0;
+}
+
+/// Puts down the next boss's warning marker once it is due. The marker turns into the boss
+/// like any other spawn warning, and `set_up_boss` takes it from there.
+pub fn spawn_boss_when_due(
+    mut commands: Commands,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    player_query: Query<&Transform, With<Player>>,
+    enemy_query: Query<AnyOf<(&Enemy, &SpawnWarning)>>,
+    asset_server: Res<AssetServer>,
+    spawn_config: Res<EnemySpawnConfig>,
+    score: Res<Score>,
+    run_time: Res<RunTime>,
+    mut boss_schedule: ResMut<BossSchedule>,
+) {
+    let boss_out = enemy_query.iter().any(|enemy| kind_of(enemy) == EnemyKind::Boss);
+    if boss_out || !boss_schedule.is_due(score.value, run_time.stopwatch.elapsed()) {
+        return;
+    }
+
+    boss_schedule.next += 1;
+
+    let window = window_query.get_single().unwrap();
+    let window_size = Vec2::new(window.width(), window.height());
+    let players: Vec<Vec2> = player_query
+        .iter()
+        .map(|transform| transform.translation.truncate())
+        .collect();
+    let position = spawn_config.find_position(window_size, &players, &mut thread_rng());
+
+    spawn_warning(&mut commands, &asset_server, EnemyKind::Boss, position, BOSS_TELEGRAPH_TIME);
+}
+
+/// Turns a freshly spawned boss-kind enemy into the scheduled boss, with its health bar.
+pub fn set_up_boss(
+    mut commands: Commands,
+    enemy_query: Query<(Entity, &Enemy), Added<Enemy>>,
+    boss_schedule: Res<BossSchedule>,
+) {
+    for (enemy_entity, enemy) in enemy_query.iter() {
+        if enemy.kind != EnemyKind::Boss {
+            continue;
+        }
+
+        let number = boss_schedule.next;
+        let health = BOSS_HEALTH + BOSS_HEALTH_STEP * (number as u32 - 1);
+        commands.entity(enemy_entity).insert((
+            Boss {
+                number,
+                health,
+                max_health: health,
+                phase: BossPhase::Drift,
+                last_hit_by: None,
+            },
+            BossAttack {
+                timer: Timer::from_seconds(BOSS_CHARGE_INTERVAL, TimerMode::Repeating),
+            },
+        ));
+        spawn_boss_health_bar(&mut commands, number);
+    }
+}
+
+fn spawn_boss_health_bar(commands: &mut Commands, number: usize) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(12.0),
+                    width: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(4.0),
+                    ..default()
+                },
+                ..default()
+            },
+            BossHealthBar,
+            StateScoped(AppState::Game),
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(format!("Boss {}", number), body_text_style()));
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        width: Val::Px(BOSS_BAR_WIDTH),
+                        height: Val::Px(BOSS_BAR_HEIGHT),
+                        ..default()
+                    },
+                    background_color: Color::rgba(0.1, 0.1, 0.1, 0.8).into(),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn((
+                        NodeBundle {
+                            style: Style {
+                                width: Val::Percent(100.0),
+                                height: Val::Percent(100.0),
+                                ..default()
+                            },
+                            background_color: BOSS_TINT.into(),
+                            ..default()
+                        },
+                        BossHealthFill,
+                    ));
+                });
+        });
+}
+
+/// Stars picked up close to the boss hurt it, so players have to brave it to win.
+pub fn damage_boss(
+    mut star_collected_event_reader: EventReader<StarCollected>,
+    mut boss_query: Query<(&Transform, &mut Boss)>,
+) {
+    for event in star_collected_event_reader.read() {
+        for (boss_transform, mut boss) in boss_query.iter_mut() {
+            if boss_transform.translation.truncate().distance(event.position) <= BOSS_DAMAGE_RADIUS {
+                boss.health = boss.health.saturating_sub(BOSS_STAR_DAMAGE);
+                boss.last_hit_by = Some(event.player);
+            }
+        }
+    }
+}
+
+pub fn run_boss_phases(
+    mut commands: Commands,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    mut boss_query: Query<(&Transform, &mut Enemy, &mut Boss, &mut BossAttack)>,
+    enemy_query: Query<AnyOf<(&Enemy, &SpawnWarning)>, Without<Boss>>,
+    player_query: Query<&Transform, With<Player>>,
+    asset_server: Res<AssetServer>,
+    spawn_config: Res<EnemySpawnConfig>,
+    director: Res<DifficultyDirector>,
+    time: Res<Time>,
+) {
+    for (boss_transform, mut enemy, mut boss, mut attack) in boss_query.iter_mut() {
+        let phase = BossPhase::for_health(boss.health, boss.max_health);
+        if phase != boss.phase {
+            boss.phase = phase;
+            let (interval, speed) = match phase {
+                BossPhase::Drift => (BOSS_CHARGE_INTERVAL, BOSS_SPEED),
+                BossPhase::Charge => (BOSS_CHARGE_INTERVAL, BOSS_SPEED * BOSS_CHARGE_SPEEDUP),
+                BossPhase::Barrage => (BOSS_BARRAGE_INTERVAL, BOSS_SPEED * BOSS_BARRAGE_SPEEDUP),
+            };
+            attack.timer = Timer::from_seconds(interval, TimerMode::Repeating);
+            enemy.speed = speed;
+        }
+
+        attack.timer.tick(time.delta());
+        if !attack.timer.just_finished() {
+            continue;
+        }
+
+        let boss_position = boss_transform.translation.truncate();
+        match boss.phase {
+            BossPhase::Drift => {}
+            BossPhase::Charge => {
+                let nearest_player = player_query
+                    .iter()
+                    .map(|transform| transform.translation.truncate())
+                    .min_by(|a, b| a.distance(boss_position).total_cmp(&b.distance(boss_position)));
+                if let Some(direction) = nearest_player.and_then(|player| (player - boss_position).try_normalize()) {
+                    enemy.direction = direction;
+                }
+            }
+            BossPhase::Barrage => {
+                let window = window_query.get_single().unwrap();
+                let window_size = Vec2::new(window.width(), window.height());
+                let margin = Vec2::splat(spawn_config.edge_margin).min(window_size / 2.0);
+                // Darts count towards the enemy cap like any other spawn, so a volley only fills the room left.
+                let room = director.max_enemies().saturating_sub(count_regular_enemies(&enemy_query));
+
+                let positions = (0..BOSS_BARRAGE_COUNT).map(|index| {
+                    let angle = std::f32::consts::TAU * index as f32 / BOSS_BARRAGE_COUNT as f32;
+                    (boss_position + Vec2::from_angle(angle) * BOSS_SIZE).clamp(margin, window_size - margin)
+                });
+                // Darts that would appear too close to someone are left out.
+                let clear_of_players = |position: &Vec2| {
+                    player_query.iter().all(|transform| {
+                        transform.translation.truncate().distance(*position) >= spawn_config.min_player_distance
+                    })
+                };
+
+                for position in positions.filter(clear_of_players).take(room) {
+                    spawn_warning(&mut commands, &asset_server, EnemyKind::Dart, position, spawn_config.telegraph_time);
+                }
+            }
+        }
+    }
+}
+
+pub fn defeat_boss(
+    mut commands: Commands,
+    boss_query: Query<(Entity, &Boss)>,
+    health_bar_query: Query<Entity, With<BossHealthBar>>,
+    asset_server: Res<AssetServer>,
+    mut boss_defeated_event_writer: EventWriter<BossDefeated>,
+) {
+    for (boss_entity, boss) in boss_query.iter() {
+        if boss.health > 0 {
+            continue;
+        }
+
+        commands.entity(boss_entity).despawn_recursive();
+        for health_bar_entity in health_bar_query.iter() {
+            commands.entity(health_bar_entity).despawn_recursive();
+        }
+        commands.spawn(AudioBundle {
+            source: asset_server.load("audio/explosionCrunch_000.ogg"),
+            settings: PlaybackSettings::DESPAWN,
+        });
+        boss_defeated_event_writer.send(BossDefeated {
+            boss: boss.number,
+            player: boss.last_hit_by.unwrap_or_default(),
+            bonus: BOSS_BONUS * boss.number as u32,
+        });
+    }
+}
+
+/// The bonus lands in `Score`, and from there in the game over total and the high-score table.
+pub fn award_boss_bonus(
+    mut boss_defeated_event_reader: EventReader<BossDefeated>,
+    mut score: ResMut<Score>,
+) {
+    for event in boss_defeated_event_reader.read() {
+        score.add(event.player, event.bonus);
+    }
+}
+
+pub fn update_boss_health_bar(
+    boss_query: Query<&Boss, Changed<Boss>>,
+    mut fill_query: Query<&mut Style, With<BossHealthFill>>,
+) {
+    for boss in boss_query.iter() {
+        let percent = 100.0 * boss.health as f32 / boss.max_health.max(1) as f32;
+        for mut style in fill_query.iter_mut() {
+            style.width = Val::Percent(percent);
+        }
+    }
+}
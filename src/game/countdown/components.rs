@@ -0,0 +1,20 @@
+use bevy::prelude::*;
+
+
+#[derive(Component)]
+pub struct CountdownScreen;
+
+#[derive(Component)]
+pub struct CountdownText;
+
+/// "Go!" shown as the simulation starts, fading out over `timer`.
+#[derive(Component)]
+pub struct GoBanner {
+    pub timer: Timer,
+}
+
+/// Arrow showing which way `enemy` will head once the countdown ends.
+#[derive(Component)]
+pub struct DirectionTelegraph {
+    pub enemy: Entity,
+}
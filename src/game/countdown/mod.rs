@@ -0,0 +1,44 @@
+use bevy::prelude::*;
+
+pub mod components;
+pub mod resources;
+mod systems;
+
+use crate::state_scoped::despawn_scoped_to;
+use crate::AppState;
+
+use self::{resources::*, systems::*};
+
+use super::{RunRestartSet, SimulationState};
+
+pub const GO_BANNER_TIME: f32 = 0.6;
+pub const TELEGRAPH_LENGTH: f32 = 48.0;
+pub const TELEGRAPH_WIDTH: f32 = 6.0;
+
+pub struct CountdownPlugin;
+
+impl Plugin for CountdownPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Countdown>()
+            .add_systems(OnEnter(SimulationState::Countdown), spawn_countdown)
+            // Restarting mid-countdown doesn't re-enter the state, so the countdown starts over here.
+            .add_systems(
+                Update,
+                despawn_scoped_to(SimulationState::Countdown).in_set(RunRestartSet::Cleanup),
+            )
+            .add_systems(
+                Update,
+                spawn_countdown
+                    .in_set(RunRestartSet::Setup)
+                    .run_if(in_state(SimulationState::Countdown)),
+            )
+            .add_systems(
+                Update,
+                (tick_countdown, update_countdown_text, telegraph_enemy_directions)
+                    .chain()
+                    .run_if(in_state(AppState::Game))
+                    .run_if(in_state(SimulationState::Countdown)),
+            )
+            .add_systems(Update, fade_go_banner);
+    }
+}
@@ -0,0 +1,15 @@
+use bevy::prelude::*;
+
+
+#[derive(Resource)]
+pub struct Countdown {
+    pub timer: Timer,
+}
+
+impl Default for Countdown {
+    fn default() -> Self {
+        Self {
+            timer: Timer::from_seconds(3.0, TimerMode::Once),
+        }
+    }
+}
@@ -0,0 +1,166 @@
+use bevy::prelude::*;
+
+use crate::game::enemy::components::Enemy;
+use crate::game::SimulationState;
+use crate::settings::resources::Settings;
+use crate::state_scoped::StateScoped;
+use crate::AppState;
+use crate::ui::styles::*;
+
+use super::components::*;
+use super::resources::*;
+use super::{GO_BANNER_TIME, TELEGRAPH_LENGTH, TELEGRAPH_WIDTH};
+
+
+pub fn spawn_countdown(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    mut countdown: ResMut<Countdown>,
+) {
+    if settings.countdown_seconds == 0 {
+        commands.insert_resource(NextState(Some(SimulationState::Running)));
+        return;
+    }
+
+    countdown.timer = Timer::from_seconds(settings.countdown_seconds as f32, TimerMode::Once);
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: screen_style(),
+                ..default()
+            },
+            CountdownScreen,
+            StateScoped(SimulationState::Countdown),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    settings.countdown_seconds.to_string(),
+                    TextStyle {
+                        font_size: 120.0,
+                        ..title_text_style()
+                    },
+                ),
+                CountdownText,
+            ));
+        });
+}
+
+pub fn tick_countdown(
+    mut commands: Commands,
+    mut countdown: ResMut<Countdown>,
+    time: Res<Time>,
+) {
+    countdown.timer.tick(time.delta());
+
+    if countdown.timer.just_finished() {
+        commands.insert_resource(NextState(Some(SimulationState::Running)));
+        commands
+            .spawn((
+                NodeBundle {
+                    style: screen_style(),
+                    ..default()
+                },
+                GoBanner {
+                    timer: Timer::from_seconds(GO_BANNER_TIME, TimerMode::Once),
+                },
+                StateScoped(AppState::Game),
+            ))
+            .with_children(|parent| {
+                parent.spawn(TextBundle::from_section(
+                    "Go!",
+                    TextStyle {
+                        font_size: 120.0,
+                        ..title_text_style()
+                    },
+                ));
+            });
+    }
+}
+
+pub fn update_countdown_text(
+    countdown: Res<Countdown>,
+    mut text_query: Query<&mut Text, With<CountdownText>>,
+) {
+    let seconds_left = countdown.timer.remaining_secs().ceil().max(1.0) as u32;
+
+    for mut text in text_query.iter_mut() {
+        let label = seconds_left.to_string();
+        if text.sections[0].value != label {
+            text.sections[0].value = label;
+        }
+    }
+}
+
+/// Keeps an arrow in front of every enemy pointing where it is about to move.
+pub fn telegraph_enemy_directions(
+    mut commands: Commands,
+    enemy_query: Query<(Entity, &Transform, &Enemy), Without<DirectionTelegraph>>,
+    mut telegraph_query: Query<(Entity, &DirectionTelegraph, &mut Transform, &mut Sprite)>,
+    time: Res<Time>,
+) {
+    let alpha = 0.5 + 0.4 * (time.elapsed_seconds() * 8.0).sin();
+    let mut telegraphed = Vec::new();
+
+    for (telegraph_entity, telegraph, mut transform, mut sprite) in telegraph_query.iter_mut() {
+        let Ok((_, enemy_transform, enemy)) = enemy_query.get(telegraph.enemy) else {
+            commands.entity(telegraph_entity).despawn();
+            continue;
+        };
+
+        *transform = telegraph_transform(enemy_transform, enemy);
+        sprite.color.set_a(alpha);
+        telegraphed.push(telegraph.enemy);
+    }
+
+    for (enemy_entity, enemy_transform, enemy) in enemy_query.iter() {
+        if telegraphed.contains(&enemy_entity) {
+            continue;
+        }
+
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: Color::rgba(1.0, 0.3, 0.3, alpha),
+                    custom_size: Some(Vec2::new(TELEGRAPH_LENGTH, TELEGRAPH_WIDTH)),
+                    ..default()
+                },
+                transform: telegraph_transform(enemy_transform, enemy),
+                ..default()
+            },
+            DirectionTelegraph {
+                enemy: enemy_entity,
+            },
+            StateScoped(SimulationState::Countdown),
+        ));
+    }
+}
+
+fn telegraph_transform(enemy_transform: &Transform, enemy: &Enemy) -> Transform {
+    let offset = enemy.direction * (enemy.size + TELEGRAPH_LENGTH) / 2.0;
+
+    Transform::from_translation(enemy_transform.translation + offset.extend(1.0))
+        .with_rotation(Quat::from_rotation_z(enemy.direction.y.atan2(enemy.direction.x)))
+}
+
+pub fn fade_go_banner(
+    mut commands: Commands,
+    mut banner_query: Query<(Entity, &mut GoBanner, &Children)>,
+    mut text_query: Query<&mut Text>,
+    time: Res<Time>,
+) {
+    for (banner_entity, mut banner, children) in banner_query.iter_mut() {
+        banner.timer.tick(time.delta());
+
+        if banner.timer.finished() {
+            commands.entity(banner_entity).despawn_recursive();
+            continue;
+        }
+
+        let mut text_iter = text_query.iter_many_mut(children);
+        while let Some(mut text) = text_iter.fetch_next() {
+            text.sections[0].style.color.set_a(banner.timer.percent_left());
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use bevy::prelude::*;
+
+pub mod resources;
+mod systems;
+
+use crate::AppState;
+
+use self::{resources::*, systems::*};
+
+use super::loader::RonAssetLoader;
+use super::{RunRestartSet, SimulationState};
+
+pub const SECONDS_PER_LEVEL: f32 = 30.0;
+pub const POINTS_PER_LEVEL: u32 = 15;
+pub const MAX_DIFFICULTY_LEVEL: u32 = 10;
+pub const ENEMY_SPAWN_TIME_STEP: f32 = 0.4;
+pub const MIN_ENEMY_SPAWN_TIME: f32 = 1.5;
+/// Extra enemy speed per level, as a fraction of each enemy's base speed.
+pub const ENEMY_SPEED_STEP: f32 = 0.08;
+pub const MAX_ENEMIES: usize = 8;
+pub const MAX_ENEMIES_STEP: usize = 2;
+/// Named with the `.difficulty.ron` extension so only the curve loader picks it up.
+pub const DIFFICULTY_FILE: &str = "game.difficulty.ron";
+
+pub struct DifficultyPlugin;
+
+impl Plugin for DifficultyPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DifficultyDirector>()
+            .init_asset::<DifficultyCurve>()
+            .init_asset_loader::<RonAssetLoader<DifficultyCurve>>()
+            .add_systems(Startup, load_difficulty_curve)
+            .add_systems(Update, apply_difficulty_curve.run_if(on_event::<AssetEvent<DifficultyCurve>>()))
+            .add_systems(OnEnter(AppState::Game), reset_difficulty)
+            .add_systems(Update, reset_difficulty.in_set(RunRestartSet::Setup))
+            .add_systems(
+                Update,
+                update_difficulty
+                    .run_if(in_state(AppState::Game))
+                    .run_if(in_state(SimulationState::Running)),
+            )
+            .add_systems(
+                Update,
+                apply_difficulty
+                    .after(RunRestartSet::Restart)
+                    .run_if(in_state(AppState::Game))
+                    .run_if(resource_changed::<DifficultyDirector>()),
+            );
+    }
+}
@@ -0,0 +1,188 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::game::enemy::components::EnemyKind;
+use crate::game::enemy::resources::ENEMY_SPAWN_TIME;
+use crate::game::loader::RonAsset;
+
+use super::{
+    ENEMY_SPAWN_TIME_STEP, ENEMY_SPEED_STEP, MAX_DIFFICULTY_LEVEL, MAX_ENEMIES, MAX_ENEMIES_STEP, MIN_ENEMY_SPAWN_TIME,
+    POINTS_PER_LEVEL, SECONDS_PER_LEVEL,
+};
+
+
+/// How a run ramps up. Every `seconds_per_level` survived and every `points_per_level`
+/// scored adds a level, up to `max_level`; each level then tightens the numbers below.
+/// Loaded from `DIFFICULTY_FILE`; fields left out of it keep the defaults here.
+#[derive(Asset, TypePath, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DifficultyCurve {
+    pub seconds_per_level: f32,
+    pub points_per_level: u32,
+    pub max_level: u32,
+    /// Spawn interval at level 1, how much each level takes off it and how short it can get.
+    pub spawn_interval: f32,
+    pub spawn_interval_step: f32,
+    pub min_spawn_interval: f32,
+    pub speed_step: f32,
+    /// Live enemies allowed at level 1, and how many more each level allows.
+    pub max_enemies: usize,
+    pub max_enemies_step: usize,
+    /// Added to a kind's spawn weight per level. Negative steps phase a kind out.
+    pub weight_steps: Vec<(EnemyKind, f32)>,
+}
+
+impl Default for DifficultyCurve {
+    fn default() -> Self {
+        Self {
+            seconds_per_level: SECONDS_PER_LEVEL,
+            points_per_level: POINTS_PER_LEVEL,
+            max_level: MAX_DIFFICULTY_LEVEL,
+            spawn_interval: ENEMY_SPAWN_TIME,
+            spawn_interval_step: ENEMY_SPAWN_TIME_STEP,
+            min_spawn_interval: MIN_ENEMY_SPAWN_TIME,
+            speed_step: ENEMY_SPEED_STEP,
+            max_enemies: MAX_ENEMIES,
+            max_enemies_step: MAX_ENEMIES_STEP,
+            weight_steps: vec![
+                (EnemyKind::Bouncer, -0.4),
+                (EnemyKind::Homing, 0.3),
+                (EnemyKind::ZigZag, 0.3),
+                (EnemyKind::Tank, 0.2),
+                (EnemyKind::Dart, 0.3),
+            ],
+        }
+    }
+}
+
+impl RonAsset for DifficultyCurve {
+    const EXTENSIONS: &'static [&'static str] = &["difficulty.ron"];
+}
+
+/// Keeps `DIFFICULTY_FILE` loaded for the whole session.
+#[derive(Resource)]
+pub struct DifficultyCurveHandle(pub Handle<DifficultyCurve>);
+
+/// Tracks the current difficulty level and turns it into spawn and speed numbers.
+#[derive(Resource, Debug)]
+pub struct DifficultyDirector {
+    pub curve: DifficultyCurve,
+    /// Starts at 1.
+    pub level: u32,
+}
+
+impl Default for DifficultyDirector {
+    fn default() -> Self {
+        Self {
+            curve: DifficultyCurve::default(),
+            level: 1,
+        }
+    }
+}
+
+impl DifficultyDirector {
+    pub fn level_for(&self, elapsed: Duration, score: u32) -> u32 {
+        let from_time = (elapsed.as_secs_f32() / self.curve.seconds_per_level.max(f32::EPSILON)) as u32;
+        let from_score = score / self.curve.points_per_level.max(1);
+
+        (1 + from_time + from_score).min(self.curve.max_level.max(1))
+    }
+
+    /// Levels gained since the start of the run.
+    fn steps(&self) -> u32 {
+        self.level.saturating_sub(1)
+    }
+
+    pub fn spawn_interval(&self) -> f32 {
+        let interval = self.curve.spawn_interval - self.curve.spawn_interval_step * self.steps() as f32;
+        interval.max(self.curve.min_spawn_interval)
+    }
+
+    pub fn speed_multiplier(&self) -> f32 {
+        1.0 + self.curve.speed_step * self.steps() as f32
+    }
+
+    pub fn max_enemies(&self) -> usize {
+        self.curve.max_enemies + self.curve.max_enemies_step * self.steps() as usize
+    }
+
+    /// Archetype weights shifted by this level's steps, never below zero.
+    pub fn spawn_weights(&self) -> Vec<(EnemyKind, f32)> {
+        EnemyKind::ALL
+            .into_iter()
+            .map(|kind| {
+                let step = self
+                    .curve
+                    .weight_steps
+                    .iter()
+                    .find(|(stepped_kind, _)| *stepped_kind == kind)
+                    .map_or(0.0, |(_, step)| *step);
+                (kind, (kind.archetype().weight + step * self.steps() as f32).max(0.0))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn level_rises_with_time_and_score() {
+        let director = DifficultyDirector::default();
+
+        assert_eq!(director.level_for(Duration::ZERO, 0), 1);
+        assert_eq!(director.level_for(Duration::from_secs_f32(SECONDS_PER_LEVEL), 0), 2);
+        assert_eq!(director.level_for(Duration::ZERO, POINTS_PER_LEVEL * 2), 3);
+        assert_eq!(director.level_for(Duration::from_secs_f32(SECONDS_PER_LEVEL), POINTS_PER_LEVEL), 3);
+    }
+
+    #[test]
+    fn level_is_capped() {
+        let director = DifficultyDirector::default();
+
+        assert_eq!(director.level_for(Duration::from_secs(100_000), 100_000), MAX_DIFFICULTY_LEVEL);
+    }
+
+    #[test]
+    fn first_level_uses_archetype_weights() {
+        let director = DifficultyDirector::default();
+
+        for (kind, weight) in director.spawn_weights() {
+            assert_eq!(weight, kind.archetype().weight);
+        }
+    }
+
+    #[test]
+    fn weights_shift_and_never_go_negative() {
+        let director = DifficultyDirector {
+            level: 100,
+            ..default()
+        };
+        let weights = director.spawn_weights();
+        let weight_of = |kind| weights.iter().find(|(weighted, _)| *weighted == kind).unwrap().1;
+
+        assert_eq!(weight_of(EnemyKind::Bouncer), 0.0);
+        assert!(weight_of(EnemyKind::Homing) > EnemyKind::Homing.archetype().weight);
+        assert!(weights.iter().all(|(_, weight)| *weight >= 0.0));
+    }
+
+    #[test]
+    fn bundled_curve_matches_defaults() {
+        let curve: DifficultyCurve = ron::from_str(include_str!("../../../assets/game.difficulty.ron")).unwrap();
+
+        assert_eq!(curve, DifficultyCurve::default());
+    }
+
+    #[test]
+    fn spawn_interval_bottoms_out() {
+        let director = DifficultyDirector {
+            level: 100,
+            ..default()
+        };
+
+        assert_eq!(director.spawn_interval(), MIN_ENEMY_SPAWN_TIME);
+    }
+}
@@ -0,0 +1,64 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::game::enemy::resources::{EnemySpawnTable, EnemySpawnTimer};
+use crate::game::resources::RunTime;
+use crate::game::score::resources::Score;
+
+use super::resources::*;
+use super::DIFFICULTY_FILE;
+
+
+pub fn load_difficulty_curve(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(DifficultyCurveHandle(asset_server.load(DIFFICULTY_FILE)));
+}
+
+/// Swaps in the curve from `DIFFICULTY_FILE` once it loads, and again whenever it's edited.
+/// Until then, or if it fails to load, the built-in default curve is used.
+pub fn apply_difficulty_curve(
+    mut asset_event_reader: EventReader<AssetEvent<DifficultyCurve>>,
+    curve_handle: Res<DifficultyCurveHandle>,
+    curves: Res<Assets<DifficultyCurve>>,
+    mut director: ResMut<DifficultyDirector>,
+) {
+    let id = curve_handle.0.id();
+    if !asset_event_reader
+        .read()
+        .any(|event| event.is_loaded_with_dependencies(id) || event.is_modified(id))
+    {
+        return;
+    }
+
+    if let Some(curve) = curves.get(id) {
+        director.curve = curve.clone();
+    }
+}
+
+pub fn reset_difficulty(mut director: ResMut<DifficultyDirector>) {
+    director.level = 1;
+}
+
+pub fn update_difficulty(
+    mut director: ResMut<DifficultyDirector>,
+    run_time: Res<RunTime>,
+    score: Res<Score>,
+) {
+    let level = director.level_for(run_time.stopwatch.elapsed(), score.value);
+
+    // Only write on a level change, so `apply_difficulty` isn't rerun every frame.
+    if director.level != level {
+        director.level = level;
+    }
+}
+
+pub fn apply_difficulty(
+    director: Res<DifficultyDirector>,
+    mut enemy_spawn_timer: ResMut<EnemySpawnTimer>,
+    mut spawn_table: ResMut<EnemySpawnTable>,
+) {
+    enemy_spawn_timer
+        .timer
+        .set_duration(Duration::from_secs_f32(director.spawn_interval()));
+    spawn_table.weights = director.spawn_weights();
+}
@@ -1,7 +1,129 @@
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::game::boss::{BOSS_SIZE, BOSS_SPEED, BOSS_TINT};
+
+use super::{ENEMY_SIZE, ENEMY_SPEED};
 
 
 #[derive(Component)]
 pub struct Enemy {
     pub direction: Vec2,
-}
\ No newline at end of file
+    pub kind: EnemyKind,
+    pub speed: f32,
+    /// Speed won or lost in the last collision, on top of `speed`. Wears off over time.
+    pub bounce_speed: f32,
+    /// Diameter in pixels, used for both the sprite and collisions.
+    pub size: f32,
+}
+
+impl Enemy {
+    pub fn current_speed(&self) -> f32 {
+        (self.speed + self.bounce_speed).max(0.0)
+    }
+}
+
+#[derive(TypePath, Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum EnemyKind {
+    /// Goes in a straight line and bounces off the walls.
+    #[default]
+    Bouncer,
+    /// Turns towards the nearest player.
+    Homing,
+    /// Swerves left and right of its heading.
+    ZigZag,
+    /// Big and slow.
+    Tank,
+    /// Small and fast.
+    Dart,
+    /// Only ever spawned by the boss schedule, so it is left out of `ALL` and of wave files.
+    /// It doesn't count towards the enemy cap or wave completion either.
+    #[serde(skip)]
+    Boss,
+}
+
+/// Fixed stats of an enemy kind.
+pub struct EnemyArchetype {
+    pub speed: f32,
+    pub size: f32,
+    /// Multiplied onto the greyscale `ball_white_large.png`, so it is close to the colour on screen.
+    pub tint: Color,
+    /// Relative chance of being picked by the spawn table.
+    pub weight: f32,
+}
+
+impl EnemyKind {
+    pub const ALL: [EnemyKind; 5] = [
+        EnemyKind::Bouncer,
+        EnemyKind::Homing,
+        EnemyKind::ZigZag,
+        EnemyKind::Tank,
+        EnemyKind::Dart,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            EnemyKind::Bouncer => "bouncer",
+            EnemyKind::Homing => "homing enemy",
+            EnemyKind::ZigZag => "zig-zagger",
+            EnemyKind::Tank => "tank",
+            EnemyKind::Dart => "dart",
+            EnemyKind::Boss => "boss",
+        }
+    }
+
+    pub fn archetype(self) -> EnemyArchetype {
+        match self {
+            EnemyKind::Bouncer => EnemyArchetype {
+                speed: ENEMY_SPEED,
+                size: ENEMY_SIZE,
+                tint: Color::rgb(1.0, 0.35, 0.42),
+                weight: 5.0,
+            },
+            EnemyKind::Homing => EnemyArchetype {
+                speed: ENEMY_SPEED * 0.7,
+                size: ENEMY_SIZE * 0.875,
+                tint: Color::rgb(1.0, 0.4, 1.0),
+                weight: 2.0,
+            },
+            EnemyKind::ZigZag => EnemyArchetype {
+                speed: ENEMY_SPEED * 1.1,
+                size: ENEMY_SIZE * 0.875,
+                tint: Color::rgb(1.0, 0.9, 0.25),
+                weight: 2.0,
+            },
+            EnemyKind::Tank => EnemyArchetype {
+                speed: ENEMY_SPEED * 0.55,
+                size: ENEMY_SIZE * 1.6,
+                tint: Color::rgb(0.55, 0.6, 0.7),
+                weight: 1.0,
+            },
+            EnemyKind::Dart => EnemyArchetype {
+                speed: ENEMY_SPEED * 1.9,
+                size: ENEMY_SIZE * 0.55,
+                tint: Color::rgb(0.35, 0.95, 1.0),
+                weight: 1.5,
+            },
+            EnemyKind::Boss => EnemyArchetype {
+                speed: BOSS_SPEED,
+                size: BOSS_SIZE,
+                tint: BOSS_TINT,
+                weight: 0.0,
+            },
+        }
+    }
+}
+
+/// Marks where an enemy of `kind` is about to appear. It can't hurt anyone until `timer` is up.
+#[derive(Component)]
+pub struct SpawnWarning {
+    pub kind: EnemyKind,
+    pub timer: Timer,
+}
+
+/// Swerve state of a `EnemyKind::ZigZag`: every time `timer` fires it turns the other way.
+#[derive(Component)]
+pub struct ZigZag {
+    pub timer: Timer,
+    pub turning_left: bool,
+}
@@ -2,38 +2,76 @@ use bevy::prelude::*;
 
 pub mod components;
 pub mod resources;
-mod systems;
+pub mod systems;
 
+use crate::events::{EnemyCollided, WaveCleared, WaveStarted};
 use crate::AppState;
 
 use self::{resources::*, systems::*};
 
-use super::SimulationState;
+use super::loader::RonAssetLoader;
+use super::{RunRestartSet, SimulationState};
 
 pub const ENEMY_SIZE: f32 = 64.0;
 pub const ENEMY_SPEED: f32 = 200.0;
 pub const ENEMY_NUMBERS: usize = 4;
+/// How fast homing enemies can turn, in radians per second.
+pub const HOMING_TURN_RATE: f32 = 1.5;
+pub const MIN_SPAWN_DISTANCE: f32 = 250.0;
+pub const SPAWN_EDGE_MARGIN: f32 = 48.0;
+pub const SPAWN_TELEGRAPH_TIME: f32 = 1.2;
+/// Random spots tried before settling for the one furthest from every player.
+pub const SPAWN_ATTEMPTS: usize = 16;
+/// Named with the `.waves.ron` extension so only the wave loader picks it up.
+pub const WAVES_FILE: &str = "game.waves.ron";
+/// Ring formations sit this many times the minimum spawn distance away from the players.
+pub const WAVE_RING_SPREAD: f32 = 1.3;
+pub const ENDLESS_WAVE_DELAY: f32 = 5.0;
+/// How quickly speed won or lost in a collision wears off, as a fraction of it per second.
+pub const BOUNCE_SPEED_DRAG: f32 = 1.5;
+pub const ZIGZAG_INTERVAL: f32 = 0.6;
+/// How far zig-zaggers swerve either side of their heading, in radians.
+pub const ZIGZAG_ANGLE: f32 = 0.6;
 
 pub struct EnemyPlugin;
 
 impl Plugin for EnemyPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<EnemySpawnTimer>()
-            .add_systems(OnEnter(AppState::Game), spawn_enemy)
+            .init_resource::<EnemySpawnTable>()
+            .init_resource::<EnemySpawnConfig>()
+            .init_asset::<WaveSchedule>()
+            .init_asset_loader::<RonAssetLoader<WaveSchedule>>()
+            .init_resource::<WaveProgress>()
+            .add_event::<EnemyCollided>()
+            .add_event::<WaveStarted>()
+            .add_event::<WaveCleared>()
+            .add_systems(Startup, load_waves)
+            .add_systems(OnEnter(AppState::Game), (spawn_enemy, reset_enemy_spawn_timer, reset_waves))
+            .add_systems(
+                Update,
+                (spawn_enemy, reset_enemy_spawn_timer, reset_waves).in_set(RunRestartSet::Setup),
+            )
             .add_systems(
                 Update,
                 (
+                    (steer_homing_enemies, swerve_zigzag_enemies),
                     enemy_movement,
+                    collide_enemies
+                        .after(enemy_movement)
+                        .before(update_enemy_direction)
+                        .run_if(enemy_collisions_enabled),
                     update_enemy_direction,
                     confine_enemy_movement,
                     tick_enemy_spawn_timer,
                     spawn_enemy_over_time,
+                    run_waves,
+                    (pulse_spawn_warnings, activate_spawn_warnings),
                 )
                     .run_if(in_state(AppState::Game))
                     .run_if(in_state(SimulationState::Running)),
             )
-            .add_systems(OnExit(AppState::Game), despawn_enemys)
-            
+            .add_systems(Update, play_enemy_collision_sound.run_if(on_event::<EnemyCollided>()))
             ;
     }
 }
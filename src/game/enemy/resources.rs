@@ -1,4 +1,15 @@
 use bevy::prelude::*;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::game::loader::RonAsset;
+use crate::game::resources::WeightedTable;
+
+use super::components::EnemyKind;
+use super::{
+    ENDLESS_WAVE_DELAY, MIN_SPAWN_DISTANCE, SPAWN_ATTEMPTS, SPAWN_EDGE_MARGIN, SPAWN_TELEGRAPH_TIME, WAVE_RING_SPREAD,
+};
 
 pub const ENEMY_SPAWN_TIME: f32 = 5.0;
 
@@ -14,3 +25,374 @@ impl Default for EnemySpawnTimer {
         }
     }
 }
+
+/// Where and how new enemies may appear.
+#[derive(Resource)]
+pub struct EnemySpawnConfig {
+    /// Closest a new enemy may appear to any player.
+    pub min_player_distance: f32,
+    /// Gap kept between new enemies and the window edges.
+    pub edge_margin: f32,
+    /// How long the warning marker shows before the enemy becomes active.
+    pub telegraph_time: f32,
+}
+
+impl Default for EnemySpawnConfig {
+    fn default() -> Self {
+        Self {
+            min_player_distance: MIN_SPAWN_DISTANCE,
+            edge_margin: SPAWN_EDGE_MARGIN,
+            telegraph_time: SPAWN_TELEGRAPH_TIME,
+        }
+    }
+}
+
+impl EnemySpawnConfig {
+    /// A random spot inside the edge margin that keeps clear of `players`. If none of a few tries
+    /// is far enough away, e.g. on a crowded small window, settles for the one furthest away.
+    pub fn find_position(&self, window_size: Vec2, players: &[Vec2], rng: &mut impl Rng) -> Vec2 {
+        let margin = Vec2::splat(self.edge_margin).min(window_size / 2.0);
+        let distance_to_players = |position: Vec2| {
+            players
+                .iter()
+                .map(|player| player.distance(position))
+                .fold(f32::INFINITY, f32::min)
+        };
+
+        let mut best = (window_size / 2.0, f32::NEG_INFINITY);
+        for _ in 0..SPAWN_ATTEMPTS {
+            let position = Vec2::new(
+                rng.gen_range(margin.x..=window_size.x - margin.x),
+                rng.gen_range(margin.y..=window_size.y - margin.y),
+            );
+            let distance = distance_to_players(position);
+
+            if distance >= self.min_player_distance {
+                return position;
+            }
+            if distance > best.1 {
+                best = (position, distance);
+            }
+        }
+
+        best.0
+    }
+}
+
+/// Weighted table new enemies' kinds are drawn from.
+pub type EnemySpawnTable = WeightedTable<EnemyKind>;
+
+impl Default for EnemySpawnTable {
+    fn default() -> Self {
+        Self {
+            weights: EnemyKind::ALL
+                .into_iter()
+                .map(|kind| (kind, kind.archetype().weight))
+                .collect(),
+        }
+    }
+}
+
+/// Authored waves, played in order before endless generation takes over.
+#[derive(Asset, TypePath, Debug, Default, Serialize, Deserialize)]
+pub struct WaveSchedule {
+    pub waves: Vec<WaveDefinition>,
+}
+
+impl RonAsset for WaveSchedule {
+    const EXTENSIONS: &'static [&'static str] = &["waves.ron"];
+}
+
+/// Keeps `WAVES_FILE` loaded for the whole session.
+#[derive(Resource, Debug)]
+pub struct WaveScheduleHandle(pub Handle<WaveSchedule>);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WaveDefinition {
+    /// Seconds between the previous wave clearing and this one starting.
+    #[serde(default)]
+    pub delay: f32,
+    pub groups: Vec<WaveGroup>,
+    #[serde(default)]
+    pub completion: WaveCompletion,
+}
+
+/// A batch of one kind of enemy, placed together in a formation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WaveGroup {
+    pub kind: EnemyKind,
+    pub count: usize,
+    #[serde(default)]
+    pub formation: Formation,
+    /// Seconds after the wave starts.
+    #[serde(default)]
+    pub delay: f32,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Formation {
+    /// Anywhere clear of the players.
+    #[default]
+    Scattered,
+    /// Evenly spaced around the players.
+    Ring,
+    /// A row along the top or bottom edge, whichever is further from the players.
+    Line,
+    Corners,
+    /// The middle of each window edge.
+    Edges,
+}
+
+impl Formation {
+    pub const ALL: [Formation; 5] = [
+        Formation::Scattered,
+        Formation::Ring,
+        Formation::Line,
+        Formation::Corners,
+        Formation::Edges,
+    ];
+
+    /// Where `count` enemies go. Spots that land too close to a player are moved somewhere clear,
+    /// so no formation can drop an enemy on top of someone.
+    pub fn positions(
+        self,
+        count: usize,
+        window_size: Vec2,
+        players: &[Vec2],
+        spawn_config: &EnemySpawnConfig,
+        rng: &mut impl Rng,
+    ) -> Vec<Vec2> {
+        let margin = Vec2::splat(spawn_config.edge_margin).min(window_size / 2.0);
+        let min = margin;
+        let max = window_size - margin;
+        let centre = window_size / 2.0;
+        let players_centre = if players.is_empty() {
+            centre
+        } else {
+            players.iter().sum::<Vec2>() / players.len() as f32
+        };
+
+        let positions: Vec<Vec2> = match self {
+            Formation::Scattered => Vec::new(),
+            Formation::Ring => {
+                let radius = spawn_config.min_player_distance * WAVE_RING_SPREAD;
+                (0..count)
+                    .map(|index| {
+                        let angle = std::f32::consts::TAU * index as f32 / count as f32;
+                        (players_centre + Vec2::from_angle(angle) * radius).clamp(min, max)
+                    })
+                    .collect()
+            }
+            Formation::Line => {
+                let y = if players_centre.y > centre.y { min.y } else { max.y };
+                (0..count)
+                    .map(|index| {
+                        let along = (index as f32 + 0.5) / count as f32;
+                        Vec2::new(min.x + (max.x - min.x) * along, y)
+                    })
+                    .collect()
+            }
+            Formation::Corners => {
+                let corners = [min, Vec2::new(max.x, min.y), max, Vec2::new(min.x, max.y)];
+                (0..count)
+                    .map(|index| {
+                        // Later rounds step in towards the middle so they don't stack.
+                        let round = (index / corners.len()) as f32;
+                        let corner = corners[index % corners.len()];
+                        corner + (centre - corner).normalize_or_zero() * round * spawn_config.edge_margin
+                    })
+                    .collect()
+            }
+            Formation::Edges => {
+                let edges = [
+                    Vec2::new(centre.x, min.y),
+                    Vec2::new(max.x, centre.y),
+                    Vec2::new(centre.x, max.y),
+                    Vec2::new(min.x, centre.y),
+                ];
+                (0..count)
+                    .map(|index| {
+                        let round = (index / edges.len()) as f32;
+                        let edge = edges[index % edges.len()];
+                        edge + (centre - edge).normalize_or_zero() * round * spawn_config.edge_margin
+                    })
+                    .collect()
+            }
+        };
+
+        let too_close = |position: &Vec2| {
+            players
+                .iter()
+                .any(|player| player.distance(*position) < spawn_config.min_player_distance)
+        };
+
+        (0..count)
+            .map(|index| match positions.get(index) {
+                Some(position) if !too_close(position) => *position,
+                _ => spawn_config.find_position(window_size, players, rng),
+            })
+            .collect()
+    }
+}
+
+/// When a wave counts as done, letting the next one start.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum WaveCompletion {
+    /// Seconds after the wave started, once all its groups are out.
+    Survive(f32),
+    /// Live enemies, warnings included, are down to this many.
+    EnemiesAtMost(usize),
+}
+
+impl Default for WaveCompletion {
+    fn default() -> Self {
+        WaveCompletion::Survive(20.0)
+    }
+}
+
+impl WaveDefinition {
+    /// A generated wave for when the authored ones run out. It grows with `number`,
+    /// and draws kinds from the spawn table, so it follows the difficulty too.
+    pub fn endless(number: usize, spawn_table: &EnemySpawnTable, rng: &mut impl Rng) -> Self {
+        let group_count = (2 + number / 3).min(6);
+        let groups = (0..group_count)
+            .map(|index| WaveGroup {
+                kind: spawn_table.choose(rng),
+                count: (2 + number / 4).min(8),
+                formation: *Formation::ALL.choose(rng).unwrap(),
+                delay: index as f32 * 2.0,
+            })
+            .collect();
+
+        Self {
+            delay: ENDLESS_WAVE_DELAY,
+            groups,
+            completion: WaveCompletion::Survive(15.0 + group_count as f32 * 2.0),
+        }
+    }
+}
+
+/// Where the current run is in the wave sequence.
+#[derive(Resource, Debug, Default)]
+pub struct WaveProgress {
+    /// Zero-based, counting authored and endless waves alike.
+    pub index: usize,
+    /// `None` until the next wave has been picked.
+    pub wave: Option<WaveDefinition>,
+    pub started: bool,
+    /// Seconds since the wave started, or since the last one cleared while waiting to start.
+    pub elapsed: f32,
+    /// How many of each group's enemies are out. Groups wait for room under the enemy cap,
+    /// so a group can come out over several frames.
+    pub spawned: Vec<usize>,
+    /// Set once the last group is out. Completion is only checked from the frame after, when
+    /// that group's warnings show up in the enemy count.
+    pub all_out: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use super::*;
+
+    const WINDOW_SIZE: Vec2 = Vec2::new(1280.0, 720.0);
+
+    #[test]
+    fn every_formation_places_each_enemy_inside_the_window() {
+        let spawn_config = EnemySpawnConfig::default();
+        let players = [WINDOW_SIZE / 2.0];
+        let mut rng = StdRng::seed_from_u64(7);
+
+        for formation in Formation::ALL {
+            for count in [1, 4, 9] {
+                let positions = formation.positions(count, WINDOW_SIZE, &players, &spawn_config, &mut rng);
+
+                assert_eq!(positions.len(), count, "{:?}", formation);
+                for position in positions {
+                    assert!(
+                        position.cmpge(Vec2::ZERO).all() && position.cmple(WINDOW_SIZE).all(),
+                        "{:?} put an enemy at {}",
+                        formation,
+                        position
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn no_formation_drops_an_enemy_on_a_player() {
+        let spawn_config = EnemySpawnConfig::default();
+        let mut rng = StdRng::seed_from_u64(7);
+
+        // Players sitting on the spots the formations would pick.
+        for players in [
+            vec![Vec2::new(spawn_config.edge_margin, spawn_config.edge_margin)],
+            vec![Vec2::new(WINDOW_SIZE.x / 2.0, spawn_config.edge_margin)],
+            vec![Vec2::new(200.0, WINDOW_SIZE.y - spawn_config.edge_margin), WINDOW_SIZE / 2.0],
+        ] {
+            for formation in Formation::ALL {
+                for position in formation.positions(8, WINDOW_SIZE, &players, &spawn_config, &mut rng) {
+                    assert!(
+                        players
+                            .iter()
+                            .all(|player| player.distance(position) >= spawn_config.min_player_distance),
+                        "{:?} put an enemy at {} next to {:?}",
+                        formation,
+                        position,
+                        players
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn ring_surrounds_the_players() {
+        let spawn_config = EnemySpawnConfig::default();
+        let players = [WINDOW_SIZE / 2.0];
+        let mut rng = StdRng::seed_from_u64(7);
+
+        let positions = Formation::Ring.positions(4, WINDOW_SIZE, &players, &spawn_config, &mut rng);
+
+        assert!(positions[0].x > players[0].x);
+        assert!(positions[1].y > players[0].y);
+        assert!(positions[2].x < players[0].x);
+        assert!(positions[3].y < players[0].y);
+    }
+
+    #[test]
+    fn line_runs_along_the_edge_away_from_the_players() {
+        let spawn_config = EnemySpawnConfig::default();
+        let mut rng = StdRng::seed_from_u64(7);
+
+        let low = [Vec2::new(WINDOW_SIZE.x / 2.0, 100.0)];
+        for position in Formation::Line.positions(5, WINDOW_SIZE, &low, &spawn_config, &mut rng) {
+            assert_eq!(position.y, WINDOW_SIZE.y - spawn_config.edge_margin);
+        }
+
+        let high = [Vec2::new(WINDOW_SIZE.x / 2.0, WINDOW_SIZE.y - 100.0)];
+        for position in Formation::Line.positions(5, WINDOW_SIZE, &high, &spawn_config, &mut rng) {
+            assert_eq!(position.y, spawn_config.edge_margin);
+        }
+    }
+
+    #[test]
+    fn corners_step_inwards_instead_of_stacking() {
+        let spawn_config = EnemySpawnConfig::default();
+        let players = [WINDOW_SIZE / 2.0];
+        let mut rng = StdRng::seed_from_u64(7);
+
+        let positions = Formation::Corners.positions(8, WINDOW_SIZE, &players, &spawn_config, &mut rng);
+
+        assert_eq!(positions[0], Vec2::splat(spawn_config.edge_margin));
+        for index in 0..4 {
+            let corner = positions[index];
+            let next_round = positions[index + 4];
+            assert_ne!(corner, next_round);
+            assert!(next_round.distance(WINDOW_SIZE / 2.0) < corner.distance(WINDOW_SIZE / 2.0));
+        }
+    }
+}
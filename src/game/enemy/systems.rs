@@ -1,95 +1,281 @@
+use bevy::asset::LoadState;
+use bevy::ecs::query::ReadOnlyWorldQuery;
 use bevy::prelude::*;
 use bevy::window::PrimaryWindow;
 
 use rand::prelude::*;
 
+use crate::events::{EnemyCollided, WaveCleared, WaveStarted};
+use crate::state_scoped::StateScoped;
+use crate::AppState;
+
 use super::components::*;
 use super::resources::*;
-use super::{ENEMY_NUMBERS, ENEMY_SIZE, ENEMY_SPEED};
+use crate::game::difficulty::resources::DifficultyDirector;
+use crate::game::player::components::Player;
+use crate::game::powerup::components::{PowerUpEffects, PowerUpKind};
+use crate::game::powerup::SLOW_TIME_FACTOR;
+use crate::game::stats::resources::RunStats;
+use crate::settings::resources::Settings;
+
+use super::{BOUNCE_SPEED_DRAG, ENEMY_NUMBERS, HOMING_TURN_RATE, WAVES_FILE, ZIGZAG_ANGLE, ZIGZAG_INTERVAL};
 
 
+/// The opening wave. It needs no warning markers, as the countdown already shows where it is headed.
 pub fn spawn_enemy(
     mut commands: Commands,
     window_query: Query<&Window, With<PrimaryWindow>>,
+    player_query: Query<&Transform, With<Player>>,
     asset_server: Res<AssetServer>,
+    spawn_table: Res<EnemySpawnTable>,
+    spawn_config: Res<EnemySpawnConfig>,
 ) {
     let window = window_query.get_single().unwrap();
+    let window_size = Vec2::new(window.width(), window.height());
+    let mut rng = thread_rng();
+    let players = player_positions(&player_query, window_size);
 
     for _ in 0..ENEMY_NUMBERS {
-        let rand_x = random::<f32>() * window.width();
-        let rand_y = random::<f32>() * window.height();
-        commands.spawn((
-            SpriteBundle {
-                transform: Transform::from_xyz(rand_x, rand_y, 0.0),
-                texture: asset_server.load("sprites/ball_red_large.png"),
+        let position = spawn_config.find_position(window_size, &players, &mut rng);
+        let kind = spawn_table.choose(&mut rng);
+        spawn_enemy_of_kind(&mut commands, &asset_server, kind, position);
+    }
+}
+
+/// Spawns a `kind` enemy at `position`, heading off in a random direction.
+pub fn spawn_enemy_of_kind(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    kind: EnemyKind,
+    position: Vec2,
+) {
+    let archetype = kind.archetype();
+    let heading = Vec2::from_angle(random::<f32>() * std::f32::consts::TAU);
+    let turning_left: bool = random();
+
+    // Zig-zaggers start off to one side of their heading and swing across it from there.
+    let direction = match (kind, turning_left) {
+        (EnemyKind::ZigZag, true) => Vec2::from_angle(-ZIGZAG_ANGLE).rotate(heading),
+        (EnemyKind::ZigZag, false) => Vec2::from_angle(ZIGZAG_ANGLE).rotate(heading),
+        _ => heading,
+    };
+
+    let mut enemy = commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: archetype.tint,
+                custom_size: Some(Vec2::splat(archetype.size)),
                 ..default()
             },
-            Enemy {
-                direction: Vec2::new(random::<f32>(), random::<f32>()).normalize(),
-            },
-        ));
+            transform: Transform::from_translation(position.extend(0.0)),
+            texture: asset_server.load("sprites/ball_white_large.png"),
+            ..default()
+        },
+        Enemy {
+            direction,
+            kind,
+            speed: archetype.speed,
+            bounce_speed: 0.0,
+            size: archetype.size,
+        },
+        StateScoped(AppState::Game),
+    ));
+
+    if kind == EnemyKind::ZigZag {
+        enemy.insert(ZigZag {
+            timer: Timer::from_seconds(ZIGZAG_INTERVAL, TimerMode::Repeating),
+            turning_left,
+        });
     }
 }
 
 
-pub fn despawn_enemys(
-    mut commands: Commands,
-    enemy_query: Query<Entity, With<Enemy>>,
+pub fn enemy_movement(
+    mut enemy_query: Query<(&mut Transform, &mut Enemy)>,
+    effects_query: Query<&PowerUpEffects>,
+    director: Res<DifficultyDirector>,
+    time: Res<Time>,
 ) {
-    for enemy_entity in enemy_query.iter() {
-        commands.entity(enemy_entity).despawn();
+    let mut speed_multiplier = director.speed_multiplier();
+    if effects_query.iter().any(|effects| effects.is_active(PowerUpKind::SlowTime)) {
+        speed_multiplier *= SLOW_TIME_FACTOR;
+    }
+
+    for (mut transform, mut enemy) in enemy_query.iter_mut() {
+        let direction = Vec3::new(enemy.direction.x, enemy.direction.y, 0.0);
+        transform.translation += direction * enemy.current_speed() * speed_multiplier * time.delta_seconds();
+
+        if enemy.bounce_speed != 0.0 {
+            enemy.bounce_speed *= (1.0 - BOUNCE_SPEED_DRAG * time.delta_seconds()).max(0.0);
+            if enemy.bounce_speed.abs() < 1.0 {
+                enemy.bounce_speed = 0.0;
+            }
+        }
     }
 }
 
+/// Homing enemies turn towards the nearest player, at most `HOMING_TURN_RATE` radians a second.
+pub fn steer_homing_enemies(
+    mut enemy_query: Query<(&Transform, &mut Enemy)>,
+    player_query: Query<&Transform, With<Player>>,
+    time: Res<Time>,
+) {
+    let max_turn = HOMING_TURN_RATE * time.delta_seconds();
 
-pub fn enemy_movement(mut enemy_query: Query<(&mut Transform, &Enemy)>, time: Res<Time>) {
-    for (mut transform, enemy) in enemy_query.iter_mut() {
-        let direction = Vec3::new(enemy.direction.x, enemy.direction.y, 0.0);
-        transform.translation += direction * ENEMY_SPEED * time.delta_seconds();
+    for (transform, mut enemy) in enemy_query.iter_mut() {
+        if enemy.kind != EnemyKind::Homing {
+            continue;
+        }
+
+        let position = transform.translation.truncate();
+        let nearest_player = player_query
+            .iter()
+            .map(|player_transform| player_transform.translation.truncate())
+            .min_by(|a, b| a.distance_squared(position).total_cmp(&b.distance_squared(position)));
+        let Some(to_player) = nearest_player.and_then(|player| (player - position).try_normalize()) else {
+            continue;
+        };
+
+        let angle = enemy.direction.angle_between(to_player).clamp(-max_turn, max_turn);
+        enemy.direction = Vec2::from_angle(angle).rotate(enemy.direction);
     }
 }
 
+pub fn swerve_zigzag_enemies(mut enemy_query: Query<(&mut Enemy, &mut ZigZag)>, time: Res<Time>) {
+    for (mut enemy, mut zigzag) in enemy_query.iter_mut() {
+        zigzag.timer.tick(time.delta());
+        if !zigzag.timer.just_finished() {
+            continue;
+        }
+
+        // Turning twice as far as the swerve crosses back over the original heading.
+        let angle = if zigzag.turning_left { 2.0 } else { -2.0 } * ZIGZAG_ANGLE;
+        enemy.direction = Vec2::from_angle(angle).rotate(enemy.direction);
+        zigzag.turning_left = !zigzag.turning_left;
+    }
+}
+
+pub fn enemy_collisions_enabled(settings: Res<Settings>) -> bool {
+    settings.enemy_collisions
+}
+
+/// Bounces overlapping enemies off each other as if they were billiard balls weighing their area,
+/// so a dart glances off a tank while the tank barely notices. The speed each one comes away with
+/// wears back off to its own over the next moments.
+pub fn collide_enemies(
+    mut enemy_query: Query<(Entity, &mut Transform, &mut Enemy)>,
+    mut enemy_collided_event_writer: EventWriter<EnemyCollided>,
+) {
+    let mut combinations = enemy_query.iter_combinations_mut();
+
+    while let Some([(entity_a, mut transform_a, mut enemy_a), (entity_b, mut transform_b, mut enemy_b)]) =
+        combinations.fetch_next()
+    {
+        let offset = (transform_b.translation - transform_a.translation).truncate();
+        let distance = offset.length();
+        let touching_distance = (enemy_a.size + enemy_b.size) / 2.0;
+        if distance >= touching_distance {
+            continue;
+        }
+
+        // Points from `a` to `b`.
+        let normal = offset.try_normalize().unwrap_or(Vec2::X);
+        let mass_a = enemy_a.size * enemy_a.size;
+        let mass_b = enemy_b.size * enemy_b.size;
+        let total_mass = mass_a + mass_b;
+
+        // Push them apart, the lighter one further.
+        let overlap = touching_distance - distance;
+        transform_a.translation -= (normal * overlap * mass_b / total_mass).extend(0.0);
+        transform_b.translation += (normal * overlap * mass_a / total_mass).extend(0.0);
+
+        let velocity_a = enemy_a.direction * enemy_a.current_speed();
+        let velocity_b = enemy_b.direction * enemy_b.current_speed();
+        let closing_speed = (velocity_a - velocity_b).dot(normal);
+        if closing_speed <= 0.0 {
+            // Already moving apart, e.g. still overlapping from last frame.
+            continue;
+        }
+
+        let impulse = 2.0 * closing_speed / total_mass;
+        let bounced_a = velocity_a - normal * impulse * mass_b;
+        let bounced_b = velocity_b + normal * impulse * mass_a;
+        enemy_a.direction = bounced_a.try_normalize().unwrap_or(-normal);
+        enemy_b.direction = bounced_b.try_normalize().unwrap_or(normal);
+        enemy_a.bounce_speed = bounced_a.length() - enemy_a.speed;
+        enemy_b.bounce_speed = bounced_b.length() - enemy_b.speed;
+
+        enemy_collided_event_writer.send(EnemyCollided {
+            enemies: [entity_a, entity_b],
+            position: transform_a.translation.truncate() + normal * enemy_a.size / 2.0,
+            impact: closing_speed,
+        });
+    }
+}
+
+/// One pluck per frame however many enemies collided, so a pile-up doesn't blast the speakers.
+pub fn play_enemy_collision_sound(
+    mut commands: Commands,
+    mut enemy_collided_event_reader: EventReader<EnemyCollided>,
+    asset_server: Res<AssetServer>,
+) {
+    enemy_collided_event_reader.clear();
+
+    commands.spawn(AudioBundle {
+        source: asset_server.load("audio/pluck_002.ogg"),
+        settings: PlaybackSettings::DESPAWN,
+    });
+}
+
 pub fn update_enemy_direction(
     mut enemy_query: Query<(&Transform, &mut Enemy)>,
     window_query: Query<&Window, With<PrimaryWindow>>,
     mut commands: Commands,
     asset_server: Res<AssetServer>,
+    mut run_stats: ResMut<RunStats>,
 ) {
     let window = window_query.get_single().unwrap();
 
-    let half_enemy_size = ENEMY_SIZE / 2.0; // 32.0
-    let x_min = 0.0 + half_enemy_size;
-    let x_max = window.width() - half_enemy_size;
-    let y_min = 0.0 + half_enemy_size;
-    let y_max = window.height() - half_enemy_size;
-
     for (transform, mut enemy) in enemy_query.iter_mut() {
+        let half_enemy_size = enemy.size / 2.0;
+        let x_min = 0.0 + half_enemy_size;
+        let x_max = window.width() - half_enemy_size;
+        let y_min = 0.0 + half_enemy_size;
+        let y_max = window.height() - half_enemy_size;
+
         let mut direction_changed = false;
 
         let translation = transform.translation;
 
-        if translation.x <= x_min || translation.x >= x_max {
+        // Only turn back while heading into the wall, or enemies that steer can get stuck in it.
+        if (translation.x <= x_min && enemy.direction.x < 0.0)
+            || (translation.x >= x_max && enemy.direction.x > 0.0)
+        {
             enemy.direction.x *= -1.0;
             direction_changed = true;
         }
-        if translation.y <= y_min || translation.y >= y_max {
+        if (translation.y <= y_min && enemy.direction.y < 0.0)
+            || (translation.y >= y_max && enemy.direction.y > 0.0)
+        {
             enemy.direction.y *= -1.0;
             direction_changed = true;
         }
 
         if direction_changed {
+            run_stats.wall_bounces += 1;
+
             let sound_effect_1 = asset_server.load("audio/pluck_001.ogg");
             let sound_effect_2 = asset_server.load("audio/pluck_002.ogg");
 
             if random::<f32>() > 0.5 {
                 commands.spawn(AudioBundle {
                     source: sound_effect_1,
-                    ..default()
+                    settings: PlaybackSettings::DESPAWN,
                 });
             } else {
                 commands.spawn(AudioBundle {
                     source: sound_effect_2,
-                    ..default()
+                    settings: PlaybackSettings::DESPAWN,
                 });
             }
         }
@@ -99,18 +285,18 @@ pub fn update_enemy_direction(
 
 
 pub fn confine_enemy_movement(
-    mut enemy_query: Query<&mut Transform, With<Enemy>>,
+    mut enemy_query: Query<(&mut Transform, &Enemy)>,
     window_query: Query<&Window, With<PrimaryWindow>>,
 ) {
     let window = window_query.get_single().unwrap();
 
-    let half_enemy_size = ENEMY_SIZE / 2.0;
-    let x_min = 0.0 + half_enemy_size;
-    let x_max = window.width() - half_enemy_size;
-    let y_min = 0.0 + half_enemy_size;
-    let y_max = window.height() - half_enemy_size;
+    for (mut transform, enemy) in enemy_query.iter_mut() {
+        let half_enemy_size = enemy.size / 2.0;
+        let x_min = 0.0 + half_enemy_size;
+        let x_max = window.width() - half_enemy_size;
+        let y_min = 0.0 + half_enemy_size;
+        let y_max = window.height() - half_enemy_size;
 
-    for mut transform in enemy_query.iter_mut() {
         let mut translation = transform.translation;
 
         // Bound the enemy x position
@@ -133,6 +319,10 @@ pub fn confine_enemy_movement(
 
 
 
+pub fn reset_enemy_spawn_timer(mut enemy_spawn_timer: ResMut<EnemySpawnTimer>) {
+    enemy_spawn_timer.timer.reset();
+}
+
 pub fn tick_enemy_spawn_timer(mut enemy_spawn_timer: ResMut<EnemySpawnTimer>, time: Res<Time>) {
     enemy_spawn_timer.timer.tick(time.delta());
 }
@@ -140,24 +330,230 @@ pub fn tick_enemy_spawn_timer(mut enemy_spawn_timer: ResMut<EnemySpawnTimer>, ti
 pub fn spawn_enemy_over_time(
     mut commands: Commands,
     window_query: Query<&Window, With<PrimaryWindow>>,
+    player_query: Query<&Transform, With<Player>>,
+    // Pending warnings count too, or a burst of them could overshoot the cap.
+    enemy_query: Query<AnyOf<(&Enemy, &SpawnWarning)>>,
     asset_server: Res<AssetServer>,
+    director: Res<DifficultyDirector>,
     enemy_spawn_timer: Res<EnemySpawnTimer>,
+    spawn_table: Res<EnemySpawnTable>,
+    spawn_config: Res<EnemySpawnConfig>,
 ) {
-    if enemy_spawn_timer.timer.finished() {
+    if enemy_spawn_timer.timer.finished() && count_regular_enemies(&enemy_query) < director.max_enemies() {
         let window = window_query.get_single().unwrap();
+        let window_size = Vec2::new(window.width(), window.height());
+        let mut rng = thread_rng();
+
+        let players = player_positions(&player_query, window_size);
+        let position = spawn_config.find_position(window_size, &players, &mut rng);
+        let kind = spawn_table.choose(&mut rng);
+        spawn_warning(&mut commands, &asset_server, kind, position, spawn_config.telegraph_time);
+    }
+}
+
+/// The kind of an enemy, or of the one a warning is about to turn into.
+pub fn kind_of((enemy, warning): (Option<&Enemy>, Option<&SpawnWarning>)) -> EnemyKind {
+    match (enemy, warning) {
+        (Some(enemy), _) => enemy.kind,
+        (None, Some(warning)) => warning.kind,
+        (None, None) => unreachable!("`AnyOf` matches at least one"),
+    }
+}
 
-        let rand_x = window.width() * random::<f32>();
-        let rand_y = window.height() * random::<f32>();
+/// Enemies that are out or on their way, as counted against the enemy cap and wave completion.
+/// The boss is left out.
+pub fn count_regular_enemies<F: ReadOnlyWorldQuery>(enemy_query: &Query<AnyOf<(&Enemy, &SpawnWarning)>, F>) -> usize {
+    enemy_query
+        .iter()
+        .filter(|enemy| kind_of(*enemy) != EnemyKind::Boss)
+        .count()
+}
+
+/// Puts down a warning marker that turns into a `kind` enemy after `telegraph_time` seconds.
+pub fn spawn_warning(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    kind: EnemyKind,
+    position: Vec2,
+    telegraph_time: f32,
+) {
+    let archetype = kind.archetype();
 
-        commands.spawn((
-            SpriteBundle {
-                transform: Transform::from_xyz(rand_x, rand_y, 0.0),
-                texture: asset_server.load("sprites/ball_red_large.png"),
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: archetype.tint.with_a(0.0),
+                custom_size: Some(Vec2::splat(archetype.size)),
                 ..default()
             },
-            Enemy {
-                direction: Vec2::new(random::<f32>(), random::<f32>()).normalize(),
-            },
-        ));
+            transform: Transform::from_translation(position.extend(0.0)),
+            texture: asset_server.load("sprites/ball_white_large.png"),
+            ..default()
+        },
+        SpawnWarning {
+            kind,
+            timer: Timer::from_seconds(telegraph_time, TimerMode::Once),
+        },
+        StateScoped(AppState::Game),
+    ));
+}
+
+pub fn load_waves(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(WaveScheduleHandle(asset_server.load(WAVES_FILE)));
+}
+
+pub fn reset_waves(mut wave_progress: ResMut<WaveProgress>) {
+    *wave_progress = WaveProgress::default();
+}
+
+/// Steps through the waves: waits out each one's delay, lets its groups out on time, as far as
+/// the difficulty's enemy cap allows, and moves on once its completion condition is met.
+pub fn run_waves(
+    mut commands: Commands,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    player_query: Query<&Transform, With<Player>>,
+    enemy_query: Query<AnyOf<(&Enemy, &SpawnWarning)>>,
+    asset_server: Res<AssetServer>,
+    wave_schedule: Res<WaveScheduleHandle>,
+    wave_schedules: Res<Assets<WaveSchedule>>,
+    spawn_table: Res<EnemySpawnTable>,
+    spawn_config: Res<EnemySpawnConfig>,
+    director: Res<DifficultyDirector>,
+    mut wave_progress: ResMut<WaveProgress>,
+    mut wave_started_event_writer: EventWriter<WaveStarted>,
+    mut wave_cleared_event_writer: EventWriter<WaveCleared>,
+    time: Res<Time>,
+) {
+    let waves: &[WaveDefinition] = match wave_schedules.get(&wave_schedule.0) {
+        Some(schedule) => &schedule.waves,
+        // The asset server has already logged why; every wave is generated instead.
+        None if asset_server.load_state(&wave_schedule.0) == LoadState::Failed => &[],
+        None => return,
+    };
+
+    let mut rng = thread_rng();
+    let progress = &mut *wave_progress;
+    let wave = progress.wave.get_or_insert_with(|| {
+        waves
+            .get(progress.index)
+            .cloned()
+            .unwrap_or_else(|| WaveDefinition::endless(progress.index, &spawn_table, &mut rng))
+    });
+    progress.elapsed += time.delta_seconds();
+
+    if !progress.started {
+        if progress.elapsed < wave.delay {
+            return;
+        }
+        progress.started = true;
+        progress.elapsed = 0.0;
+        progress.spawned = vec![0; wave.groups.len()];
+        wave_started_event_writer.send(WaveStarted {
+            wave: progress.index + 1,
+            endless: progress.index >= waves.len(),
+        });
+    }
+
+    let window = window_query.get_single().unwrap();
+    let window_size = Vec2::new(window.width(), window.height());
+    let players = player_positions(&player_query, window_size);
+
+    let mut live_enemies = count_regular_enemies(&enemy_query);
+
+    for (group, spawned) in wave.groups.iter().zip(progress.spawned.iter_mut()) {
+        if *spawned >= group.count || progress.elapsed < group.delay {
+            continue;
+        }
+
+        let room = director.max_enemies().saturating_sub(live_enemies);
+        let count = (group.count - *spawned).min(room);
+        if count == 0 {
+            continue;
+        }
+
+        // The formation is laid out for the whole group; a deferred rest fills the spots left over.
+        for position in group
+            .formation
+            .positions(group.count, window_size, &players, &spawn_config, &mut rng)
+            .into_iter()
+            .skip(*spawned)
+            .take(count)
+        {
+            spawn_warning(&mut commands, &asset_server, group.kind, position, spawn_config.telegraph_time);
+        }
+        *spawned += count;
+        live_enemies += count;
+    }
+
+    if !progress.all_out {
+        progress.all_out = wave
+            .groups
+            .iter()
+            .zip(progress.spawned.iter())
+            .all(|(group, spawned)| *spawned >= group.count);
+        return;
+    }
+
+    let complete = match wave.completion {
+        WaveCompletion::Survive(seconds) => progress.elapsed >= seconds,
+        WaveCompletion::EnemiesAtMost(count) => count_regular_enemies(&enemy_query) <= count,
+    };
+
+    if complete {
+        wave_cleared_event_writer.send(WaveCleared {
+            wave: progress.index + 1,
+        });
+        *progress = WaveProgress {
+            index: progress.index + 1,
+            ..default()
+        };
+    }
+}
+
+/// Where the players are, or the middle of the window while none have spawned yet,
+/// as is the case on the frame a run starts.
+fn player_positions(player_query: &Query<&Transform, With<Player>>, window_size: Vec2) -> Vec<Vec2> {
+    let players: Vec<Vec2> = player_query
+        .iter()
+        .map(|transform| transform.translation.truncate())
+        .collect();
+
+    if players.is_empty() {
+        vec![window_size / 2.0]
+    } else {
+        players
+    }
+}
+
+/// Fades the warning marker in and out, faster as the enemy is about to appear.
+pub fn pulse_spawn_warnings(
+    mut warning_query: Query<(&mut SpawnWarning, &mut Sprite, &mut Transform)>,
+    time: Res<Time>,
+) {
+    for (mut warning, mut sprite, mut transform) in warning_query.iter_mut() {
+        warning.timer.tick(time.delta());
+
+        let progress = warning.timer.percent();
+        let pulse = (warning.timer.elapsed_secs() * (6.0 + 18.0 * progress)).sin() * 0.5 + 0.5;
+        sprite.color.set_a(0.15 + 0.45 * pulse);
+        transform.scale = Vec3::splat(0.8 + 0.3 * pulse);
+    }
+}
+
+pub fn activate_spawn_warnings(
+    mut commands: Commands,
+    warning_query: Query<(Entity, &SpawnWarning, &Transform)>,
+    asset_server: Res<AssetServer>,
+) {
+    for (warning_entity, warning, transform) in warning_query.iter() {
+        if warning.timer.finished() {
+            commands.entity(warning_entity).despawn();
+            spawn_enemy_of_kind(
+                &mut commands,
+                &asset_server,
+                warning.kind,
+                transform.translation.truncate(),
+            );
+        }
     }
 }
@@ -0,0 +1,20 @@
+use bevy::prelude::*;
+
+
+#[derive(Component)]
+pub struct Hud;
+
+/// One line of the HUD, filled in by the system that watches its source.
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+pub enum HudText {
+    Score,
+    Combo,
+    Lives,
+    Dash,
+    PowerUps,
+    RunTime,
+    Difficulty,
+    EnemyCount,
+    NextSpawn,
+    HighScore,
+}
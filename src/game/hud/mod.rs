@@ -0,0 +1,40 @@
+use bevy::prelude::*;
+
+pub mod components;
+mod systems;
+
+use crate::settings::resources::Settings;
+use crate::AppState;
+
+use self::systems::*;
+
+use super::RunRestartSet;
+
+pub const HUD_FONT_SIZE: f32 = 24.0;
+pub const HUD_MARGIN: f32 = 12.0;
+
+pub struct HudPlugin;
+
+impl Plugin for HudPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::Game), spawn_hud)
+            .add_systems(Update, spawn_hud.in_set(RunRestartSet::Setup))
+            .add_systems(
+                Update,
+                (
+                    update_score_text,
+                    update_combo_text,
+                    update_lives_text,
+                    update_dash_text,
+                    update_power_ups_text,
+                    update_run_time_text,
+                    update_difficulty_text,
+                    update_enemy_count_text,
+                    update_next_spawn_text,
+                    update_high_score_text,
+                )
+                    .run_if(in_state(AppState::Game)),
+            )
+            .add_systems(Update, apply_hud_settings.run_if(resource_changed::<Settings>()));
+    }
+}
@@ -0,0 +1,349 @@
+use bevy::prelude::*;
+
+use crate::game::difficulty::resources::DifficultyDirector;
+use crate::game::enemy::components::{Enemy, EnemyKind};
+use crate::game::enemy::resources::EnemySpawnTimer;
+use crate::game::player::components::{DashCooldown, Lives, Player};
+use crate::game::powerup::components::PowerUpEffects;
+use crate::game::resources::{format_run_time, RunTime};
+use crate::game::score::resources::{ComboWindow, HighScores, Score};
+use crate::settings::resources::{HudAnchor, Settings};
+use crate::settings::{MAX_HUD_SCALE, MIN_HUD_SCALE};
+use crate::state_scoped::StateScoped;
+use crate::AppState;
+
+use super::components::*;
+use super::{HUD_FONT_SIZE, HUD_MARGIN};
+
+
+pub fn spawn_hud(mut commands: Commands, settings: Res<Settings>) {
+    let lines = [
+        HudText::Score,
+        HudText::Combo,
+        HudText::Lives,
+        HudText::Dash,
+        HudText::PowerUps,
+        HudText::RunTime,
+        HudText::Difficulty,
+        HudText::EnemyCount,
+        HudText::NextSpawn,
+        HudText::HighScore,
+    ];
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: hud_style(settings.hud_anchor),
+                ..default()
+            },
+            Hud,
+            StateScoped(AppState::Game),
+        ))
+        .with_children(|parent| {
+            for line in lines {
+                // Left empty here; each update system fills its line in the frame it is added.
+                parent.spawn((
+                    TextBundle::from_section("", hud_text_style(&settings)),
+                    line,
+                ));
+            }
+        });
+}
+
+pub fn apply_hud_settings(
+    settings: Res<Settings>,
+    mut hud_query: Query<&mut Style, With<Hud>>,
+    mut text_query: Query<&mut Text, With<HudText>>,
+) {
+    for mut style in hud_query.iter_mut() {
+        *style = hud_style(settings.hud_anchor);
+    }
+
+    let font_size = hud_font_size(&settings);
+    for mut text in text_query.iter_mut() {
+        for section in text.sections.iter_mut() {
+            if section.style.font_size != font_size {
+                section.style.font_size = font_size;
+            }
+        }
+    }
+}
+
+pub fn update_score_text(
+    score: Res<Score>,
+    mut text_query: Query<(&mut Text, Ref<HudText>)>,
+) {
+    for (mut text, line) in text_query.iter_mut() {
+        if *line == HudText::Score && (score.is_changed() || line.is_added()) {
+            let label = if score.players.len() > 1 {
+                let split: Vec<(usize, String)> = score
+                    .players
+                    .iter()
+                    .enumerate()
+                    .map(|(player, points)| (player, points.to_string()))
+                    .collect();
+                format!("Score: {} ({})", score.value, per_player(split))
+            } else {
+                format!("Score: {}", score.value)
+            };
+            set_text(&mut text, label);
+        }
+    }
+}
+
+pub fn update_combo_text(
+    score: Res<Score>,
+    combo_window: Res<ComboWindow>,
+    mut text_query: Query<(&mut Text, Ref<HudText>)>,
+) {
+    let changed = score.is_changed() || combo_window.is_changed();
+
+    for (mut text, line) in text_query.iter_mut() {
+        if *line == HudText::Combo && (changed || line.is_added()) {
+            let label = if score.combo > 0 {
+                format!(
+                    "Combo: {} x{} ({:.1}s)",
+                    score.combo,
+                    score.multiplier,
+                    combo_window.timer.remaining_secs()
+                )
+            } else {
+                format!("Combo: - (best {})", score.best_combo)
+            };
+            set_text(&mut text, label);
+        }
+    }
+}
+
+pub fn update_lives_text(
+    lives_query: Query<(&Player, Ref<Lives>)>,
+    mut removed_lives: RemovedComponents<Lives>,
+    mut text_query: Query<(&mut Text, Ref<HudText>)>,
+) {
+    let changed = removed_lives.read().count() > 0
+        || lives_query.iter().any(|(_, lives)| lives.is_changed());
+
+    for (mut text, line) in text_query.iter_mut() {
+        if *line == HudText::Lives && (changed || line.is_added()) {
+            let lives = lives_query
+                .iter()
+                .map(|(player, lives)| (player.index, lives.remaining.to_string()))
+                .collect();
+            set_text(&mut text, format!("Lives: {}", per_player(lives)));
+        }
+    }
+}
+
+pub fn update_dash_text(
+    dash_query: Query<(&Player, Ref<DashCooldown>)>,
+    mut removed_dashes: RemovedComponents<DashCooldown>,
+    mut text_query: Query<(&mut Text, Ref<HudText>)>,
+) {
+    let changed = removed_dashes.read().count() > 0
+        || dash_query.iter().any(|(_, dash_cooldown)| dash_cooldown.is_changed());
+
+    for (mut text, line) in text_query.iter_mut() {
+        if *line == HudText::Dash && (changed || line.is_added()) {
+            let dashes = dash_query
+                .iter()
+                .map(|(player, dash_cooldown)| {
+                    let label = if dash_cooldown.timer.finished() {
+                        "ready".to_string()
+                    } else {
+                        format!("{:.1}s", dash_cooldown.timer.remaining_secs())
+                    };
+                    (player.index, label)
+                })
+                .collect();
+            set_text(&mut text, format!("Dash: {}", per_player(dashes)));
+        }
+    }
+}
+
+/// One tinted badge per active effect, e.g. "S2 14" for a two-charge shield with 14s left,
+/// so the line takes several sections rather than going through `set_text`.
+/// `PowerUpEffects` only reads as changed once a second while effects count down.
+pub fn update_power_ups_text(
+    effects_query: Query<(&Player, Ref<PowerUpEffects>)>,
+    mut removed_effects: RemovedComponents<PowerUpEffects>,
+    mut text_query: Query<(&mut Text, Ref<HudText>)>,
+) {
+    let changed = removed_effects.read().count() > 0
+        || effects_query.iter().any(|(_, effects)| effects.is_changed());
+    let style = text_query
+        .iter()
+        .find(|(_, line)| **line == HudText::PowerUps && (changed || line.is_added()))
+        .map(|(text, _)| text.sections[0].style.clone());
+    let Some(style) = style else {
+        return;
+    };
+
+    let mut players: Vec<(&Player, Ref<PowerUpEffects>)> = effects_query.iter().collect();
+    players.sort_by_key(|(player, _)| player.index);
+
+    let mut sections = vec![TextSection::new("Power-ups:", style.clone())];
+    for (player, effects) in players.iter() {
+        if effects.active.is_empty() {
+            continue;
+        }
+        if players.len() > 1 {
+            sections.push(TextSection::new(format!("  P{}", player.index + 1), style.clone()));
+        }
+        for effect in effects.active.iter() {
+            let charges = if effect.charges > 1 { effect.charges.to_string() } else { String::new() };
+            let seconds_left = effect.timer.remaining_secs().ceil() as u32;
+            sections.push(TextSection::new(
+                format!(" {}{} {}", effect.kind.icon(), charges, seconds_left),
+                TextStyle {
+                    color: effect.kind.tint(),
+                    ..style.clone()
+                },
+            ));
+        }
+    }
+    if sections.len() == 1 {
+        sections.push(TextSection::new(" -", style));
+    }
+
+    for (mut text, line) in text_query.iter_mut() {
+        let unchanged = text.sections.len() == sections.len()
+            && text
+                .sections
+                .iter()
+                .zip(sections.iter())
+                .all(|(old, new)| old.value == new.value && old.style.color == new.style.color);
+        if *line == HudText::PowerUps && !unchanged {
+            text.sections = sections.clone();
+        }
+    }
+}
+
+pub fn update_run_time_text(
+    run_time: Res<RunTime>,
+    mut text_query: Query<(&mut Text, Ref<HudText>)>,
+) {
+    for (mut text, line) in text_query.iter_mut() {
+        if *line == HudText::RunTime && (run_time.is_changed() || line.is_added()) {
+            set_text(&mut text, format!("Time: {}", format_run_time(run_time.stopwatch.elapsed())));
+        }
+    }
+}
+
+pub fn update_difficulty_text(
+    director: Res<DifficultyDirector>,
+    mut text_query: Query<(&mut Text, Ref<HudText>)>,
+) {
+    for (mut text, line) in text_query.iter_mut() {
+        if *line == HudText::Difficulty && (director.is_changed() || line.is_added()) {
+            set_text(&mut text, format!("Level: {}", director.level));
+        }
+    }
+}
+
+pub fn update_enemy_count_text(
+    enemy_query: Query<&Enemy>,
+    added_enemy_query: Query<(), Added<Enemy>>,
+    mut removed_enemies: RemovedComponents<Enemy>,
+    mut text_query: Query<(&mut Text, Ref<HudText>)>,
+) {
+    // Drain the removals every frame so old ones don't pile up.
+    let enemies_changed = removed_enemies.read().count() > 0 || !added_enemy_query.is_empty();
+
+    for (mut text, line) in text_query.iter_mut() {
+        if *line == HudText::EnemyCount && (enemies_changed || line.is_added()) {
+            let count = enemy_query.iter().filter(|enemy| enemy.kind != EnemyKind::Boss).count();
+            set_text(&mut text, format!("Enemies: {}", count));
+        }
+    }
+}
+
+pub fn update_next_spawn_text(
+    enemy_spawn_timer: Res<EnemySpawnTimer>,
+    mut text_query: Query<(&mut Text, Ref<HudText>)>,
+) {
+    for (mut text, line) in text_query.iter_mut() {
+        if *line == HudText::NextSpawn && (enemy_spawn_timer.is_changed() || line.is_added()) {
+            let seconds_left = enemy_spawn_timer.timer.remaining_secs().ceil() as u32;
+            set_text(&mut text, format!("Next enemy: {}s", seconds_left));
+        }
+    }
+}
+
+pub fn update_high_score_text(
+    score: Res<Score>,
+    high_scores: Res<HighScores>,
+    mut text_query: Query<(&mut Text, Ref<HudText>)>,
+) {
+    let changed = score.is_changed() || high_scores.is_changed();
+
+    for (mut text, line) in text_query.iter_mut() {
+        if *line == HudText::HighScore && (changed || line.is_added()) {
+            let label = match high_scores.scores.first() {
+                Some((_, best)) if score.value > *best => format!("Best: {} (beaten!)", best),
+                Some((name, best)) => format!("Best: {} ({})", best, name),
+                None => "Best: -".to_string(),
+            };
+            set_text(&mut text, label);
+        }
+    }
+}
+
+/// Joins `(player index, value)` pairs as "P1 3  P2 1", or just the value when playing alone.
+fn per_player(mut values: Vec<(usize, String)>) -> String {
+    if let [(0, value)] = values.as_slice() {
+        return value.clone();
+    }
+    if values.is_empty() {
+        return "-".to_string();
+    }
+
+    values.sort_by_key(|(player, _)| *player);
+    values
+        .into_iter()
+        .map(|(player, value)| format!("P{} {}", player + 1, value))
+        .collect::<Vec<_>>()
+        .join("  ")
+}
+
+/// Only touches the text when it actually reads differently, so the layout isn't redone every frame.
+fn set_text(text: &mut Text, value: String) {
+    if text.sections[0].value != value {
+        text.sections[0].value = value;
+    }
+}
+
+fn hud_style(anchor: HudAnchor) -> Style {
+    let margin = Val::Px(HUD_MARGIN);
+    let (top, bottom) = match anchor {
+        HudAnchor::TopLeft | HudAnchor::TopRight => (margin, Val::Auto),
+        HudAnchor::BottomLeft | HudAnchor::BottomRight => (Val::Auto, margin),
+    };
+    let (left, right, align_items) = match anchor {
+        HudAnchor::TopLeft | HudAnchor::BottomLeft => (margin, Val::Auto, AlignItems::FlexStart),
+        HudAnchor::TopRight | HudAnchor::BottomRight => (Val::Auto, margin, AlignItems::FlexEnd),
+    };
+
+    Style {
+        position_type: PositionType::Absolute,
+        top,
+        bottom,
+        left,
+        right,
+        flex_direction: FlexDirection::Column,
+        align_items,
+        row_gap: Val::Px(4.0),
+        ..default()
+    }
+}
+
+fn hud_font_size(settings: &Settings) -> f32 {
+    HUD_FONT_SIZE * settings.hud_scale.clamp(MIN_HUD_SCALE, MAX_HUD_SCALE)
+}
+
+fn hud_text_style(settings: &Settings) -> TextStyle {
+    TextStyle {
+        font_size: hud_font_size(settings),
+        color: Color::WHITE,
+        ..default()
+    }
+}
@@ -0,0 +1,45 @@
+use std::marker::PhantomData;
+
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, AsyncReadExt, LoadContext};
+use bevy::prelude::*;
+use bevy::utils::BoxedFuture;
+use serde::de::DeserializeOwned;
+
+/// Game data authored as RON under `assets/`, e.g. `game.waves.ron`.
+pub trait RonAsset: Asset + DeserializeOwned {
+    /// Each data file gets its own double extension, so loaders don't claim each other's files.
+    const EXTENSIONS: &'static [&'static str];
+}
+
+/// Reads a `RonAsset` through the asset server, so data loads wherever the game's sprites do.
+pub struct RonAssetLoader<A>(PhantomData<A>);
+
+impl<A> Default for RonAssetLoader<A> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<A: RonAsset> AssetLoader for RonAssetLoader<A> {
+    type Asset = A;
+    type Settings = ();
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<A, Self::Error>> {
+        Box::pin(async move {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await?;
+            Ok(ron::de::from_bytes::<A>(&bytes)?)
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        A::EXTENSIONS
+    }
+}
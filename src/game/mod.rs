@@ -1,18 +1,36 @@
 use bevy::prelude::*;
 
+pub mod boss;
+mod countdown;
+pub mod difficulty;
 pub mod enemy;
-mod player;
+mod hud;
+pub mod loader;
+mod pause;
+pub mod player;
+pub mod powerup;
+pub mod resources;
 pub mod star;
 pub mod score;
+pub mod stats;
 mod systems;
 
+use boss::BossPlugin;
+use countdown::CountdownPlugin;
+use difficulty::DifficultyPlugin;
 use enemy::EnemyPlugin;
+use hud::HudPlugin;
+use pause::PausePlugin;
 use player::PlayerPlugin;
+use powerup::PowerUpPlugin;
 use score::ScorePlugin;
 use star::StarPlugin;
+use stats::StatsPlugin;
+use resources::*;
 use systems::*;
 
-use crate::{events::GameOver, AppState};
+use crate::state_scoped::{despawn_scoped_to, StateScopedPlugin};
+use crate::{events::{GameOver, RestartRun}, settings::SettingsMenuState, AppState};
 
 pub struct GamePlugin;
 
@@ -21,15 +39,59 @@ impl Plugin for GamePlugin {
         app
         .add_state::<SimulationState>()
         .add_event::<GameOver>()
-        .add_plugins((EnemyPlugin, StarPlugin, PlayerPlugin, ScorePlugin))
-        .add_systems(Update, toggle_simulation.run_if(in_state(AppState::Game)));
+        .add_event::<RestartRun>()
+        .init_resource::<RunTime>()
+        .add_plugins(StateScopedPlugin::<SimulationState>::default())
+        .configure_sets(
+            Update,
+            (
+                (RunRestartSet::Cleanup, RunRestartSet::Setup).chain(),
+                RunRestartSet::Cleanup.in_set(RunRestartSet::Restart),
+                RunRestartSet::Setup.in_set(RunRestartSet::Restart),
+                RunRestartSet::Restart
+                    .run_if(in_state(AppState::Game))
+                    .run_if(on_event::<RestartRun>()),
+            ),
+        )
+        .add_plugins((EnemyPlugin, BossPlugin, DifficultyPlugin, StarPlugin, PowerUpPlugin, PlayerPlugin, ScorePlugin, PausePlugin, CountdownPlugin, HudPlugin, StatsPlugin))
+        .add_systems(OnEnter(AppState::Game), (reset_run_time, start_countdown))
+        .add_systems(OnExit(AppState::Game), park_simulation)
+        .add_systems(
+            Update,
+            despawn_scoped_to(AppState::Game).in_set(RunRestartSet::Cleanup),
+        )
+        .add_systems(Update, reset_run_time.in_set(RunRestartSet::Setup))
+        .add_systems(
+            Update,
+            (toggle_simulation, restart_run)
+                .run_if(in_state(AppState::Game))
+                .run_if(in_state(SettingsMenuState::Closed)),
+        )
+        .add_systems(
+            Update,
+            tick_run_time
+                .run_if(in_state(AppState::Game))
+                .run_if(in_state(SimulationState::Running)),
+        );
     }
 }
 
 
+/// Systems that react to `RestartRun`: the old run's entities are cleared in `Cleanup`
+/// before plugins set up the new run in `Setup`, all in the same frame.
+#[derive(SystemSet, Debug, Hash, PartialEq, Eq, Clone)]
+pub enum RunRestartSet {
+    Restart,
+    Cleanup,
+    Setup,
+}
+
+
 #[derive(States, Debug, Clone, Copy, Hash, PartialEq, Eq, Default)]
 pub enum SimulationState {
    Running,
    #[default]
    Paused,
-}
\ No newline at end of file
+   /// The world is frozen while "3-2-1-Go" counts down, then switches to `Running`.
+   Countdown,
+}
@@ -0,0 +1,13 @@
+use bevy::prelude::*;
+
+
+#[derive(Component)]
+pub struct PauseMenu;
+
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+pub enum PauseMenuButton {
+    Resume,
+    RestartRun,
+    Settings,
+    QuitToMenu,
+}
@@ -0,0 +1,26 @@
+use bevy::prelude::*;
+
+pub mod components;
+mod systems;
+
+use crate::AppState;
+
+use self::systems::*;
+
+use super::SimulationState;
+
+pub struct PausePlugin;
+
+impl Plugin for PausePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(SimulationState::Paused),
+            spawn_pause_menu.run_if(in_state(AppState::Game)),
+        )
+        .add_systems(
+            Update,
+            (pause_on_focus_lost, interact_with_pause_menu_buttons)
+                .run_if(in_state(AppState::Game)),
+        );
+    }
+}
@@ -0,0 +1,107 @@
+use bevy::prelude::*;
+use bevy::ui::FocusPolicy;
+use bevy::window::WindowFocused;
+
+use crate::events::{ButtonActivated, RestartRun};
+use crate::game::SimulationState;
+use crate::settings::SettingsMenuState;
+use crate::state_scoped::StateScoped;
+use crate::ui::components::*;
+use crate::ui::styles::*;
+use crate::ui::PANEL_LAYER;
+use crate::AppState;
+
+use super::components::*;
+
+
+pub fn spawn_pause_menu(mut commands: Commands, menu_query: Query<(), With<PauseMenu>>) {
+    if !menu_query.is_empty() {
+        return;
+    }
+
+    let buttons = [
+        (PauseMenuButton::Resume, "Resume"),
+        (PauseMenuButton::RestartRun, "Restart Run"),
+        (PauseMenuButton::Settings, "Settings"),
+        (PauseMenuButton::QuitToMenu, "Quit to Menu"),
+    ];
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: screen_style(),
+                background_color: OVERLAY_COLOR.into(),
+                focus_policy: FocusPolicy::Block,
+                z_index: ZIndex::Global(PANEL_LAYER as i32),
+                ..default()
+            },
+            FocusGroup {
+                columns: 1,
+                layer: PANEL_LAYER,
+            },
+            PauseMenu,
+            StateScoped(SimulationState::Paused),
+            // Quitting from the menu leaves the simulation paused, so the app state has to clean up too.
+            StateScoped(AppState::Game),
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section("Paused", title_text_style()));
+
+            for (index, (button, label)) in buttons.into_iter().enumerate() {
+                let mut button_entity = parent.spawn((
+                    ButtonBundle {
+                        style: button_style(),
+                        background_color: NORMAL_BUTTON_COLOR.into(),
+                        ..default()
+                    },
+                    Focusable { index },
+                    button,
+                ));
+                button_entity.with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(label, button_text_style()));
+                });
+
+                if button == PauseMenuButton::Resume {
+                    button_entity.insert(CancelButton);
+                }
+            }
+        });
+}
+
+pub fn pause_on_focus_lost(
+    mut commands: Commands,
+    mut window_focused_event_reader: EventReader<WindowFocused>,
+    simulation_state: Res<State<SimulationState>>,
+) {
+    let focus_lost = window_focused_event_reader.read().any(|event| !event.focused);
+
+    if focus_lost && *simulation_state.get() != SimulationState::Paused {
+        commands.insert_resource(NextState(Some(SimulationState::Paused)));
+    }
+}
+
+pub fn interact_with_pause_menu_buttons(
+    mut commands: Commands,
+    mut button_activated_event_reader: EventReader<ButtonActivated>,
+    mut restart_run_event_writer: EventWriter<RestartRun>,
+    button_query: Query<&PauseMenuButton>,
+) {
+    for event in button_activated_event_reader.read() {
+        match button_query.get(event.entity) {
+            Ok(PauseMenuButton::Resume) => {
+                commands.insert_resource(NextState(Some(SimulationState::Countdown)));
+            }
+            Ok(PauseMenuButton::RestartRun) => {
+                restart_run_event_writer.send(RestartRun);
+                commands.insert_resource(NextState(Some(SimulationState::Countdown)));
+            }
+            Ok(PauseMenuButton::Settings) => {
+                commands.insert_resource(NextState(Some(SettingsMenuState::Open)));
+            }
+            Ok(PauseMenuButton::QuitToMenu) => {
+                commands.insert_resource(NextState(Some(AppState::MainMenu)));
+            }
+            Err(_) => {}
+        }
+    }
+}
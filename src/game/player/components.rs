@@ -1,5 +1,87 @@
 use bevy::prelude::*;
 
+use crate::controls::resources::InputSource;
+use crate::settings::resources::MovementPreset;
 
+use super::{DASH_COOLDOWN, PLAYER_ACCELERATION, PLAYER_DRAG, PLAYER_SPEED};
+
+
+#[derive(Component)]
+pub struct Player {
+    /// 0 for the first player, counting up to `MAX_PLAYERS - 1`.
+    pub index: usize,
+}
+
+#[derive(Component, Clone, Copy)]
+pub struct ControlledBy(pub InputSource);
+
+#[derive(Component, Default)]
+pub struct Velocity(pub Vec2);
+
+/// How a player's input turns into velocity.
+#[derive(Component, Clone, Copy)]
+pub enum MovementModel {
+    /// Full speed the moment a direction is held, and a dead stop when it is let go.
+    Instant { speed: f32 },
+    /// Speeds up towards the held direction and coasts to a stop under drag.
+    Inertia {
+        acceleration: f32,
+        /// Fraction of the velocity lost per second while coasting.
+        drag: f32,
+        max_speed: f32,
+    },
+}
+
+impl MovementModel {
+    pub fn from_preset(preset: MovementPreset) -> Self {
+        match preset {
+            MovementPreset::Instant => MovementModel::Instant {
+                speed: PLAYER_SPEED,
+            },
+            MovementPreset::Inertia => MovementModel::Inertia {
+                acceleration: PLAYER_ACCELERATION,
+                drag: PLAYER_DRAG,
+                max_speed: PLAYER_SPEED,
+            },
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct Lives {
+    pub remaining: u32,
+}
+
+/// Enemies pass through the player until `timer` runs out. The sprite blinks meanwhile.
+#[derive(Component)]
+pub struct Invulnerable {
+    pub timer: Timer,
+}
+
+/// Time until the player can dash again.
+#[derive(Component)]
+pub struct DashCooldown {
+    pub timer: Timer,
+}
+
+impl Default for DashCooldown {
+    fn default() -> Self {
+        let mut timer = Timer::from_seconds(DASH_COOLDOWN, TimerMode::Once);
+        // Ready from the start of the run.
+        timer.tick(timer.duration());
+        Self { timer }
+    }
+}
+
+/// The player is mid-dash: moving at `velocity`, ignoring input, and untouchable by enemies.
+#[derive(Component)]
+pub struct Dashing {
+    pub timer: Timer,
+    pub velocity: Vec2,
+}
+
+/// Push away from the enemy that hit the player, slowing down until it is gone.
 #[derive(Component)]
-pub struct Player {}
\ No newline at end of file
+pub struct Knockback {
+    pub velocity: Vec2,
+}
\ No newline at end of file
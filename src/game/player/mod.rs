@@ -4,13 +4,39 @@ use bevy::prelude::*;
 
 
 pub mod components;
+pub mod resources;
 
 mod systems;
 
 pub const PLAYER_SPEED: f32 = 500.0;
 pub const PLAYER_SIZE: f32 = 64.0;
+pub const MAX_PLAYERS: usize = 4;
+/// Sprite tint per player index. The first player keeps the ball's own colour.
+pub const PLAYER_TINTS: [Color; MAX_PLAYERS] = [
+    Color::WHITE,
+    Color::rgb(0.5, 1.0, 0.5),
+    Color::rgb(1.0, 0.6, 1.0),
+    Color::rgb(1.0, 1.0, 0.4),
+];
+/// Within this distance of the pointer, the follow scheme eases off instead of overshooting.
+pub const FOLLOW_SLOWDOWN_DISTANCE: f32 = 120.0;
+pub const PLAYER_ACCELERATION: f32 = 2500.0;
+pub const PLAYER_DRAG: f32 = 4.0;
+pub const DASH_SPEED: f32 = 1600.0;
+pub const DASH_TIME: f32 = 0.18;
+pub const DASH_COOLDOWN: f32 = 1.5;
+pub const INVULNERABILITY_TIME: f32 = 2.0;
+pub const BLINK_INTERVAL: f32 = 0.1;
+pub const KNOCKBACK_SPEED: f32 = 900.0;
+/// How quickly knockback dies down, as a fraction of its speed lost per second.
+pub const KNOCKBACK_DRAG: f32 = 6.0;
 
-use self::systems::*;
+use crate::events::PlayerDashed;
+use crate::AppState;
+
+use self::{resources::*, systems::*};
+
+use super::{RunRestartSet, SimulationState};
 
 #[derive(SystemSet, Debug, Hash, PartialEq, Eq, Clone)]
 pub struct MovementSystemSet;
@@ -23,12 +49,38 @@ pub struct PlayerPlugin;
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
         app
-        .configure_sets(Update, MovementSystemSet.before(ConfinementSystemSet))
-        .add_systems(Startup, spawn_player)
-        .add_systems(Update, player_movement.in_set(MovementSystemSet))
+        .add_event::<PlayerDashed>()
+        .init_resource::<DownedPlayers>()
+        .configure_sets(
+            Update,
+            (
+                MovementSystemSet.before(ConfinementSystemSet),
+                (MovementSystemSet, ConfinementSystemSet)
+                    .run_if(in_state(AppState::Game))
+                    .run_if(in_state(SimulationState::Running)),
+            ),
+        )
+        .add_systems(OnEnter(AppState::Game), (spawn_players, reset_downed_players))
+        .add_systems(
+            Update,
+            (spawn_players, reset_downed_players).in_set(RunRestartSet::Setup),
+        )
+        .add_systems(
+            Update,
+            (start_dash, (player_movement, apply_dash, apply_knockback))
+                .chain()
+                .in_set(MovementSystemSet),
+        )
+        .add_systems(Update, play_dash_sound.run_if(on_event::<PlayerDashed>()))
         .add_systems(Update, confine_player_movement.in_set(ConfinementSystemSet))
-        .add_systems(Update, enemy_hit_player)
-        .add_systems(Update, player_hit_star)
+        .add_systems(
+            Update,
+            (blink_invulnerable_player, enemy_hit_player, player_hit_star)
+                .chain()
+                .after(ConfinementSystemSet)
+                .run_if(in_state(AppState::Game))
+                .run_if(in_state(SimulationState::Running)),
+        )
         ;
     }
 }
\ No newline at end of file
@@ -0,0 +1,10 @@
+use bevy::prelude::*;
+
+use crate::events::PlayerResult;
+
+
+/// Results of the players who are already out of lives this run.
+#[derive(Resource, Default)]
+pub struct DownedPlayers {
+    pub results: Vec<PlayerResult>,
+}
@@ -4,149 +4,410 @@ use bevy::window::PrimaryWindow;
 use crate::game::enemy::components::*;
 use crate::game::star::components::*;
 use crate::game::score::resources::*;
-use crate::events::GameOver;
-use crate::game::enemy::ENEMY_SIZE;
+use crate::controls::resources::{Action, ActionState, InputSource, PointerInput};
+use crate::events::{GameOver, PlayerDashed, PlayerResult, StarCollected};
+use crate::game::difficulty::resources::DifficultyDirector;
+use crate::game::powerup::components::PowerUpEffects;
+use crate::game::resources::RunTime;
+use crate::game::stats::resources::{DeathCause, RunStats};
 use crate::game::star::STAR_SIZE;
+use crate::settings::resources::{ControlScheme, Settings};
+use crate::state_scoped::StateScoped;
+use crate::AppState;
 
 use super::components::*;
-use super::{PLAYER_SIZE, PLAYER_SPEED};
+use super::resources::*;
+use super::{
+    BLINK_INTERVAL, DASH_SPEED, DASH_TIME, FOLLOW_SLOWDOWN_DISTANCE, INVULNERABILITY_TIME, KNOCKBACK_DRAG, KNOCKBACK_SPEED, MAX_PLAYERS,
+    PLAYER_SIZE, PLAYER_SPEED, PLAYER_TINTS,
+};
 
 
-pub fn spawn_player(
+/// Lines the players up across the middle of the screen. A lone player takes input from every
+/// device; otherwise the first player has the keyboard and the rest a gamepad each.
+/// Players without a connected gamepad are left out rather than spawned unable to move.
+pub fn spawn_players(
     mut commands: Commands,
     window_query: Query<&Window, With<PrimaryWindow>>,
     asset_server: Res<AssetServer>,
+    settings: Res<Settings>,
+    gamepads: Res<Gamepads>,
 ) {
     let window = window_query.get_single().unwrap();
+    let supported_players = (gamepads.iter().count() + 1).min(MAX_PLAYERS);
+    let player_count = settings.player_count.clamp(1, supported_players);
+    if player_count < settings.player_count {
+        warn!(
+            "{} players are set up but only {} gamepad(s) are connected; starting with {}",
+            settings.player_count,
+            supported_players - 1,
+            player_count
+        );
+    }
+
+    for (index, tint) in PLAYER_TINTS.into_iter().take(player_count).enumerate() {
+        let offset = (index as f32 - (player_count - 1) as f32 / 2.0) * PLAYER_SIZE * 2.0;
+        let input_source = match (player_count, index) {
+            (1, _) => InputSource::Any,
+            (_, 0) => InputSource::Keyboard,
+            (_, index) => InputSource::Gamepad(index - 1),
+        };
+
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: tint,
+                    ..default()
+                },
+                transform: Transform::from_xyz(
+                    window.width() / 2.0 + offset,
+                    window.height() / 2.0,
+                    0.0,
+                ),
+                texture: asset_server.load("sprites/ball_blue_large.png"),
+                ..default()
+            },
+            Player { index },
+            ControlledBy(input_source),
+            Velocity::default(),
+            DashCooldown::default(),
+            MovementModel::from_preset(settings.movement_preset),
+            Lives {
+                remaining: settings.starting_lives.max(1),
+            },
+            PowerUpEffects::default(),
+            StateScoped(AppState::Game),
+        ));
+    }
+}
 
-    commands.spawn((
-        SpriteBundle {
-            transform: Transform::from_xyz(window.width() / 2.0, window.height() / 2.0, 0.0),
-            texture: asset_server.load("sprites/ball_blue_large.png"),
-            ..default()
-        },
-        Player {},
-    ));
+pub fn reset_downed_players(mut downed_players: ResMut<DownedPlayers>) {
+    downed_players.results.clear();
 }
 
 
 pub fn player_movement(
-    keyboard_event: Res<Input<KeyCode>>,
-    mut player_query: Query<&mut Transform, With<Player>>,
+    action_state: Res<ActionState>,
+    pointer_input: Res<PointerInput>,
+    settings: Res<Settings>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    mut player_query: Query<
+        (&mut Transform, &mut Velocity, &MovementModel, &ControlledBy, &PowerUpEffects),
+        (With<Player>, Without<Dashing>),
+    >,
     time: Res<Time>,
 ) {
-    if let Ok(mut transfrom) = player_query.get_single_mut() {
-        let mut direction = Vec3::ZERO;
+    let delta = time.delta_seconds();
 
-        if keyboard_event.pressed(KeyCode::Left) || keyboard_event.pressed(KeyCode::A) {
-            direction += Vec3::new(-1.0, 0.0, 0.0);
-        }
+    for (mut transfrom, mut velocity, movement_model, controlled_by, effects) in player_query.iter_mut() {
+        // Sticks only partly pushed aim for a slower speed.
+        let mut input = action_state.move_axis_of(controlled_by.0);
 
-        if keyboard_event.pressed(KeyCode::Up) || keyboard_event.pressed(KeyCode::W) {
-            direction += Vec3::new(0.0, 1., 0.0);
+        // The pointer belongs to whoever has the keyboard.
+        if matches!(controlled_by.0, InputSource::Any | InputSource::Keyboard) {
+            match settings.control_scheme {
+                ControlScheme::Buttons => {}
+                ControlScheme::PointerFollow => {
+                    if let (Some(target), Ok(window)) =
+                        (pointer_input.position, window_query.get_single())
+                    {
+                        // Aim for where the ball can actually get to, not a spot past the wall.
+                        let bounds = player_bounds(window, effects.size_scale());
+                        let target = target.clamp(bounds.min, bounds.max);
+                        input = (target - transfrom.translation.truncate())
+                            .clamp_length_max(FOLLOW_SLOWDOWN_DISTANCE)
+                            / FOLLOW_SLOWDOWN_DISTANCE;
+                    }
+                }
+                ControlScheme::VirtualJoystick => {
+                    input = (input + pointer_input.joystick_axis()).clamp_length_max(1.0);
+                }
+            }
         }
 
-        if keyboard_event.pressed(KeyCode::Right) || keyboard_event.pressed(KeyCode::D) {
-            direction += Vec3::new(1.0, 0.0, 0.0);
-        }
+        velocity.0 = match *movement_model {
+            MovementModel::Instant { speed } => input * speed,
+            MovementModel::Inertia {
+                acceleration,
+                drag,
+                max_speed,
+            } => {
+                if input == Vec2::ZERO {
+                    velocity.0 * (1.0 - drag * delta).max(0.0)
+                } else {
+                    let target = input * max_speed;
+                    velocity.0 + (target - velocity.0).clamp_length_max(acceleration * delta)
+                }
+            }
+        };
 
-        if keyboard_event.pressed(KeyCode::Down) || keyboard_event.pressed(KeyCode::S) {
-            direction += Vec3::new(0.0, -1., 0.0);
-        }
+        transfrom.translation += velocity.0.extend(0.0) * delta;
+    }
+}
+
+/// Dashes in the held direction, or the current heading if nothing is held, once the cooldown allows.
+pub fn start_dash(
+    mut commands: Commands,
+    action_state: Res<ActionState>,
+    mut player_query: Query<
+        (Entity, &Velocity, &ControlledBy, &mut DashCooldown),
+        (With<Player>, Without<Dashing>),
+    >,
+    mut player_dashed_event_writer: EventWriter<PlayerDashed>,
+    time: Res<Time>,
+) {
+    for (player_entity, velocity, controlled_by, mut dash_cooldown) in player_query.iter_mut() {
+        dash_cooldown.timer.tick(time.delta());
 
-        if direction.length() > 0.0 {
-            direction = direction.normalize();
+        if !action_state.just_pressed_by(controlled_by.0, Action::Dash)
+            || !dash_cooldown.timer.finished()
+        {
+            continue;
         }
 
-        transfrom.translation += direction * PLAYER_SPEED * time.delta_seconds();
+        let direction = action_state
+            .move_axis_of(controlled_by.0)
+            .try_normalize()
+            .or_else(|| velocity.0.try_normalize());
+        let Some(direction) = direction else {
+            continue;
+        };
+
+        dash_cooldown.timer.reset();
+        commands.entity(player_entity).insert(Dashing {
+            timer: Timer::from_seconds(DASH_TIME, TimerMode::Once),
+            velocity: direction * DASH_SPEED,
+        });
+        player_dashed_event_writer.send(PlayerDashed {
+            player: player_entity,
+        });
     }
 }
 
+pub fn apply_dash(
+    mut commands: Commands,
+    mut player_query: Query<(Entity, &mut Transform, &mut Dashing), With<Player>>,
+    time: Res<Time>,
+) {
+    for (player_entity, mut transform, mut dashing) in player_query.iter_mut() {
+        dashing.timer.tick(time.delta());
+        transform.translation += dashing.velocity.extend(0.0) * time.delta_seconds();
+
+        if dashing.timer.finished() {
+            commands.entity(player_entity).remove::<Dashing>();
+        }
+    }
+}
+
+pub fn play_dash_sound(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn(AudioBundle {
+        source: asset_server.load("audio/pluck_002.ogg"),
+        settings: PlaybackSettings::DESPAWN.with_speed(1.5),
+    });
+}
+
 pub fn confine_player_movement(
-    mut player_query: Query<&mut Transform, With<Player>>,
+    mut player_query: Query<(&mut Transform, &mut Velocity, &PowerUpEffects), With<Player>>,
     window_query: Query<&Window, With<PrimaryWindow>>,
 ) {
     
-    if let Ok(mut transfrom) = player_query.get_single_mut() {
+    for (mut transfrom, mut velocity, effects) in player_query.iter_mut() {
         let window = window_query.get_single().unwrap();
 
-        let half_player_size = PLAYER_SIZE / 2.0;
-        let x_min = half_player_size;
-        let x_max = window.width() - half_player_size;
-        let y_min = half_player_size;
-        let y_max = window.height() - half_player_size;
+        let Rect {
+            min: Vec2 { x: x_min, y: y_min },
+            max: Vec2 { x: x_max, y: y_max },
+        } = player_bounds(window, effects.size_scale());
 
+        // Walls also stop any momentum into them, so the player doesn't stick to them.
         if transfrom.translation.x < x_min {
             transfrom.translation.x = x_min;
+            velocity.0.x = velocity.0.x.max(0.0);
         }
 
         if transfrom.translation.x > x_max {
             transfrom.translation.x = x_max;
+            velocity.0.x = velocity.0.x.min(0.0);
         }
 
         if transfrom.translation.y < y_min {
             transfrom.translation.y = y_min;
+            velocity.0.y = velocity.0.y.max(0.0);
         }
 
         if transfrom.translation.y > y_max {
             transfrom.translation.y = y_max;
+            velocity.0.y = velocity.0.y.min(0.0);
         }
     }
 }
 
 
+/// Where the centre of a player drawn at `size_scale` may go without the ball leaving the window.
+fn player_bounds(window: &Window, size_scale: f32) -> Rect {
+    let half_player_size = PLAYER_SIZE / 2.0 * size_scale;
+    Rect::new(
+        half_player_size,
+        half_player_size,
+        window.width() - half_player_size,
+        window.height() - half_player_size,
+    )
+}
+
+pub fn apply_knockback(
+    mut commands: Commands,
+    mut player_query: Query<(Entity, &mut Transform, &mut Knockback), With<Player>>,
+    time: Res<Time>,
+) {
+    for (player_entity, mut transform, mut knockback) in player_query.iter_mut() {
+        transform.translation += knockback.velocity.extend(0.0) * time.delta_seconds();
+        knockback.velocity *= (1.0 - KNOCKBACK_DRAG * time.delta_seconds()).max(0.0);
+
+        if knockback.velocity.length() < PLAYER_SPEED / 10.0 {
+            commands.entity(player_entity).remove::<Knockback>();
+        }
+    }
+}
+
+pub fn blink_invulnerable_player(
+    mut commands: Commands,
+    mut player_query: Query<(Entity, &mut Invulnerable, &mut Visibility), With<Player>>,
+    time: Res<Time>,
+) {
+    for (player_entity, mut invulnerable, mut visibility) in player_query.iter_mut() {
+        invulnerable.timer.tick(time.delta());
+
+        if invulnerable.timer.finished() {
+            *visibility = Visibility::Inherited;
+            commands.entity(player_entity).remove::<Invulnerable>();
+            continue;
+        }
+
+        let blink = (invulnerable.timer.elapsed_secs() / BLINK_INTERVAL) as u32 % 2 == 0;
+        *visibility = if blink { Visibility::Hidden } else { Visibility::Inherited };
+    }
+}
+
+/// Costs each player a life on contact with an enemy, counting only the first enemy touching them.
+/// Players out of lives leave the field, and the run is over once nobody is left.
+/// Dashing players slip through untouched.
 pub fn enemy_hit_player(
     mut commands: Commands,
-    mut player_query: Query<(Entity, &Transform), With<Player>>,
+    mut player_query: Query<
+        (Entity, &Player, &Transform, &mut Lives, &mut PowerUpEffects),
+        (Without<Invulnerable>, Without<Dashing>),
+    >,
+    all_players_query: Query<(), With<Player>>,
     mut game_over_event_writer: EventWriter<GameOver>,
-    enemy_query: Query<&Transform, With<Enemy>>,
+    enemy_query: Query<(&Transform, &Enemy)>,
     asset_server: Res<AssetServer>,
-    score: Res<Score>,
+    mut score: ResMut<Score>,
+    run_time: Res<RunTime>,
+    director: Res<DifficultyDirector>,
+    mut run_stats: ResMut<RunStats>,
+    mut downed_players: ResMut<DownedPlayers>,
 ) {
-    if let Ok((player_entity, player_transform)) = player_query.get_single_mut() {
-        for enemy_transform in enemy_query.iter() {
-            let distance = player_transform
-                .translation
-                .distance(enemy_transform.translation);
+    let mut players_left = all_players_query.iter().count();
+    let mut anyone_downed = false;
 
-            let player_radius = PLAYER_SIZE / 2.0;
-            let enemy_radius = ENEMY_SIZE / 2.0;
+    for (player_entity, player, player_transform, mut lives, mut effects) in player_query.iter_mut() {
+        let player_radius = PLAYER_SIZE / 2.0 * effects.size_scale();
+        let Some((enemy_transform, enemy)) = enemy_query.iter().find(|(enemy_transform, enemy)| {
+            player_transform
+                .translation
+                .distance(enemy_transform.translation)
+                < player_radius + enemy.size / 2.0
+        }) else {
+            continue;
+        };
 
-            if distance < player_radius + enemy_radius {
-                commands.spawn(AudioBundle {
-                    source: asset_server.load("audio/explosionCrunch_000.ogg"),
-                    ..default()
-                });
+        commands.spawn(AudioBundle {
+            source: asset_server.load("audio/explosionCrunch_000.ogg"),
+            settings: PlaybackSettings::DESPAWN,
+        });
+        score.break_combo();
 
-                commands.entity(player_entity).despawn();
-                game_over_event_writer.send(GameOver { score: score.value })
-            }
+        // A shield takes the hit instead; the knockback and grace period below still apply.
+        if !effects.absorb_hit() {
+            lives.remaining = lives.remaining.saturating_sub(1);
+        }
+        if lives.remaining == 0 {
+            run_stats.cause_of_death = Some(DeathCause {
+                player: player.index,
+                enemy: enemy.kind,
+                position: player_transform.translation.truncate(),
+            });
+            commands.entity(player_entity).despawn();
+            downed_players.results.push(PlayerResult {
+                player: player.index,
+                score: score.of_player(player.index),
+                time_survived: run_time.stopwatch.elapsed(),
+            });
+            players_left -= 1;
+            anyone_downed = true;
+            continue;
         }
+
+        let away = (player_transform.translation - enemy_transform.translation)
+            .truncate()
+            .try_normalize()
+            .unwrap_or(Vec2::Y);
+        commands.entity(player_entity).insert((
+            Invulnerable {
+                timer: Timer::from_seconds(INVULNERABILITY_TIME, TimerMode::Once),
+            },
+            Knockback {
+                velocity: away * KNOCKBACK_SPEED,
+            },
+        ));
+    }
+
+    if anyone_downed && players_left == 0 {
+        let mut players = downed_players.results.clone();
+        players.sort_by_key(|result| result.player);
+        game_over_event_writer.send(GameOver {
+            score: score.value,
+            players,
+            difficulty_level: director.level,
+            stats: RunStats {
+                time_survived: run_time.stopwatch.elapsed(),
+                ..run_stats.clone()
+            },
+        });
     }
 }
 
 pub fn player_hit_star(
     mut commands: Commands,
-    star_query: Query<(Entity, &Transform), With<Star>>,
-    player_query: Query<&Transform, With<Player>>,
+    star_query: Query<(Entity, &Transform, &Star)>,
+    player_query: Query<(&Player, &Transform, &PowerUpEffects)>,
     asset_server: Res<AssetServer>,
     mut score: ResMut<Score>,
+    mut combo_window: ResMut<ComboWindow>,
+    mut star_collected_event_writer: EventWriter<StarCollected>,
 ) {
-    if let Ok(player_transform) = player_query.get_single() {
-        for (star_entity, star_translation) in star_query.iter() {
-            let distance = player_transform
+    for (star_entity, star_translation, star) in star_query.iter() {
+        // A star touched by two players at once goes to whoever is checked first.
+        let collector = player_query.iter().find(|(_, player_transform, effects)| {
+            player_transform
                 .translation
-                .distance(star_translation.translation);
+                .distance(star_translation.translation)
+                < PLAYER_SIZE / 2.0 * effects.size_scale() + STAR_SIZE / 2.0
+        });
 
-            if distance < PLAYER_SIZE / 2.0 + STAR_SIZE / 2.0 {
-                println!("Player hit star");
-                score.value += 1;
-                commands.spawn(AudioBundle {
-                    source: asset_server.load("audio/laserLarge_000.ogg"),
-                    ..default()
-                });
-                commands.entity(star_entity).despawn();
-            }
+        if let Some((player, _, _)) = collector {
+            score.add_combo(player.index, star.value);
+            combo_window.restart(score.combo);
+            star_collected_event_writer.send(StarCollected {
+                player: player.index,
+                kind: star.kind,
+                position: star_translation.translation.truncate(),
+            });
+            commands.spawn(AudioBundle {
+                source: asset_server.load("audio/laserLarge_000.ogg"),
+                settings: PlaybackSettings::DESPAWN,
+            });
+            commands.entity(star_entity).despawn();
         }
     }
 }
@@ -0,0 +1,259 @@
+use bevy::prelude::*;
+
+use super::{MAX_EXTEND_FACTOR, MAX_SHIELD_CHARGES, SHRINK_SCALE};
+
+
+/// A pickup lying in the arena.
+#[derive(Component)]
+pub struct PowerUp {
+    pub kind: PowerUpKind,
+    /// Counts down to the pickup vanishing if nobody gets to it.
+    pub timer: Timer,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PowerUpKind {
+    /// Absorbs one hit per charge.
+    Shield,
+    /// Enemies move at half speed.
+    SlowTime,
+    /// Shrinks the player, and how close enemies have to get to hit them.
+    Shrink,
+    /// Pulls nearby stars in.
+    Magnet,
+    /// Clears out the enemies around the player at once.
+    Bomb,
+}
+
+/// What collecting a kind again does while it is still active.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StackRule {
+    /// Starts the timer over.
+    Refresh,
+    /// Adds the duration on top of what is left, up to `MAX_EXTEND_FACTOR` times the duration.
+    Extend,
+    /// Adds a charge, up to the given number, and starts the timer over.
+    Charges(u32),
+    /// Goes off on pickup and never lingers.
+    Instant,
+}
+
+impl PowerUpKind {
+    pub const ALL: [PowerUpKind; 5] = [
+        PowerUpKind::Shield,
+        PowerUpKind::SlowTime,
+        PowerUpKind::Shrink,
+        PowerUpKind::Magnet,
+        PowerUpKind::Bomb,
+    ];
+
+    /// Seconds the effect lasts.
+    pub fn duration(self) -> f32 {
+        match self {
+            PowerUpKind::Shield => 20.0,
+            PowerUpKind::SlowTime => 6.0,
+            PowerUpKind::Shrink => 10.0,
+            PowerUpKind::Magnet => 8.0,
+            PowerUpKind::Bomb => 0.0,
+        }
+    }
+
+    pub fn stack_rule(self) -> StackRule {
+        match self {
+            PowerUpKind::Shield => StackRule::Charges(MAX_SHIELD_CHARGES),
+            PowerUpKind::SlowTime => StackRule::Extend,
+            PowerUpKind::Shrink => StackRule::Refresh,
+            PowerUpKind::Magnet => StackRule::Extend,
+            PowerUpKind::Bomb => StackRule::Instant,
+        }
+    }
+
+    pub fn tint(self) -> Color {
+        match self {
+            PowerUpKind::Shield => Color::rgb(0.4, 0.7, 1.0),
+            PowerUpKind::SlowTime => Color::rgb(0.8, 0.5, 1.0),
+            PowerUpKind::Shrink => Color::rgb(0.5, 1.0, 0.8),
+            PowerUpKind::Magnet => Color::rgb(1.0, 0.4, 0.4),
+            PowerUpKind::Bomb => Color::rgb(1.0, 0.6, 0.1),
+        }
+    }
+
+    /// Single letter shown on the pickup and in the HUD.
+    pub fn icon(self) -> &'static str {
+        match self {
+            PowerUpKind::Shield => "S",
+            PowerUpKind::SlowTime => "T",
+            PowerUpKind::Shrink => "R",
+            PowerUpKind::Magnet => "M",
+            PowerUpKind::Bomb => "B",
+        }
+    }
+}
+
+/// A power-up working on a player.
+#[derive(Debug)]
+pub struct ActiveEffect {
+    pub kind: PowerUpKind,
+    pub timer: Timer,
+    /// Only used by shields; one is spent per absorbed hit.
+    pub charges: u32,
+}
+
+/// Every power-up currently working on a player.
+#[derive(Component, Debug, Default)]
+pub struct PowerUpEffects {
+    pub active: Vec<ActiveEffect>,
+}
+
+impl PowerUpEffects {
+    pub fn is_active(&self, kind: PowerUpKind) -> bool {
+        self.active.iter().any(|effect| effect.kind == kind)
+    }
+
+    /// How big the player is drawn and how close things have to get to touch them.
+    pub fn size_scale(&self) -> f32 {
+        if self.is_active(PowerUpKind::Shrink) {
+            SHRINK_SCALE
+        } else {
+            1.0
+        }
+    }
+
+    /// Starts `kind`, or stacks it onto the running effect following its `StackRule`.
+    pub fn apply(&mut self, kind: PowerUpKind) {
+        let duration = kind.duration();
+        let rule = kind.stack_rule();
+        if rule == StackRule::Instant {
+            return;
+        }
+
+        let Some(effect) = self.active.iter_mut().find(|effect| effect.kind == kind) else {
+            self.active.push(ActiveEffect {
+                kind,
+                timer: Timer::from_seconds(duration, TimerMode::Once),
+                charges: 1,
+            });
+            return;
+        };
+
+        match rule {
+            StackRule::Refresh => effect.timer.reset(),
+            StackRule::Extend => {
+                let remaining = effect.timer.remaining_secs() + duration;
+                effect.timer = Timer::from_seconds(remaining.min(duration * MAX_EXTEND_FACTOR), TimerMode::Once);
+            }
+            StackRule::Charges(max_charges) => {
+                effect.charges = (effect.charges + 1).min(max_charges);
+                effect.timer.reset();
+            }
+            StackRule::Instant => {}
+        }
+    }
+
+    /// Spends a shield charge on a hit. Returns whether there was one to spend.
+    /// A spent shield is cleaned up, and reported as expired, with the other effects.
+    pub fn absorb_hit(&mut self) -> bool {
+        match self
+            .active
+            .iter_mut()
+            .find(|effect| effect.kind == PowerUpKind::Shield && effect.charges > 0)
+        {
+            Some(shield) => {
+                shield.charges -= 1;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn effect(effects: &PowerUpEffects, kind: PowerUpKind) -> &ActiveEffect {
+        effects.active.iter().find(|effect| effect.kind == kind).unwrap()
+    }
+
+    #[test]
+    fn apply_starts_an_effect_once() {
+        let mut effects = PowerUpEffects::default();
+
+        effects.apply(PowerUpKind::Magnet);
+        effects.apply(PowerUpKind::Magnet);
+
+        assert_eq!(effects.active.len(), 1);
+        assert!(effects.is_active(PowerUpKind::Magnet));
+    }
+
+    #[test]
+    fn instant_kinds_never_linger() {
+        let mut effects = PowerUpEffects::default();
+
+        effects.apply(PowerUpKind::Bomb);
+
+        assert!(effects.active.is_empty());
+    }
+
+    #[test]
+    fn refresh_starts_the_timer_over() {
+        let mut effects = PowerUpEffects::default();
+        effects.apply(PowerUpKind::Shrink);
+        effects.active[0].timer.tick(Duration::from_secs(4));
+
+        effects.apply(PowerUpKind::Shrink);
+
+        assert_eq!(effect(&effects, PowerUpKind::Shrink).timer.elapsed(), Duration::ZERO);
+    }
+
+    #[test]
+    fn extend_adds_up_to_the_cap() {
+        let mut effects = PowerUpEffects::default();
+        let duration = PowerUpKind::SlowTime.duration();
+        effects.apply(PowerUpKind::SlowTime);
+        effects.active[0].timer.tick(Duration::from_secs_f32(duration / 2.0));
+
+        effects.apply(PowerUpKind::SlowTime);
+        assert_eq!(effect(&effects, PowerUpKind::SlowTime).timer.remaining_secs(), duration * 1.5);
+
+        effects.apply(PowerUpKind::SlowTime);
+        assert_eq!(
+            effect(&effects, PowerUpKind::SlowTime).timer.remaining_secs(),
+            duration * MAX_EXTEND_FACTOR
+        );
+    }
+
+    #[test]
+    fn shield_charges_stack_up_to_the_cap() {
+        let mut effects = PowerUpEffects::default();
+
+        for _ in 0..MAX_SHIELD_CHARGES + 2 {
+            effects.apply(PowerUpKind::Shield);
+        }
+
+        assert_eq!(effect(&effects, PowerUpKind::Shield).charges, MAX_SHIELD_CHARGES);
+    }
+
+    #[test]
+    fn absorb_hit_spends_one_charge_per_hit() {
+        let mut effects = PowerUpEffects::default();
+        effects.apply(PowerUpKind::Shield);
+        effects.apply(PowerUpKind::Shield);
+
+        assert!(effects.absorb_hit());
+        assert_eq!(effect(&effects, PowerUpKind::Shield).charges, 1);
+        assert!(effects.absorb_hit());
+        assert!(!effects.absorb_hit());
+        assert_eq!(effect(&effects, PowerUpKind::Shield).charges, 0);
+    }
+
+    #[test]
+    fn absorb_hit_needs_a_shield() {
+        let mut effects = PowerUpEffects::default();
+        effects.apply(PowerUpKind::Magnet);
+
+        assert!(!effects.absorb_hit());
+    }
+}
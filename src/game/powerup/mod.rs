@@ -0,0 +1,51 @@
+use bevy::prelude::*;
+
+pub mod components;
+pub mod resources;
+mod systems;
+
+use crate::events::{PowerUpCollected, PowerUpExpired};
+use crate::AppState;
+
+use self::{resources::*, systems::*};
+
+use super::{RunRestartSet, SimulationState};
+
+pub const POWER_UP_SIZE: f32 = 36.0;
+pub const POWER_UP_SPAWN_TIME: f32 = 15.0;
+/// Uncollected pickups vanish after this long.
+pub const POWER_UP_PICKUP_TIME: f32 = 10.0;
+pub const MAX_SHIELD_CHARGES: u32 = 3;
+/// Longest an `Extend` effect can be stacked up to, as a multiple of its own duration.
+pub const MAX_EXTEND_FACTOR: f32 = 2.0;
+pub const SLOW_TIME_FACTOR: f32 = 0.5;
+pub const SHRINK_SCALE: f32 = 0.5;
+pub const MAGNET_RADIUS: f32 = 220.0;
+pub const MAGNET_SPEED: f32 = 400.0;
+pub const BOMB_RADIUS: f32 = 260.0;
+
+pub struct PowerUpPlugin;
+
+impl Plugin for PowerUpPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PowerUpSpawnTimer>()
+            .add_event::<PowerUpCollected>()
+            .add_event::<PowerUpExpired>()
+            .add_systems(OnEnter(AppState::Game), reset_power_up_spawn_timer)
+            .add_systems(Update, reset_power_up_spawn_timer.in_set(RunRestartSet::Setup))
+            .add_systems(
+                Update,
+                (
+                    tick_power_up_spawn_timer,
+                    spawn_power_ups_over_time,
+                    expire_power_up_pickups,
+                    collect_power_ups,
+                    tick_power_up_effects,
+                    (shrink_players, pull_stars),
+                )
+                    .chain()
+                    .run_if(in_state(AppState::Game))
+                    .run_if(in_state(SimulationState::Running)),
+            );
+    }
+}
@@ -0,0 +1,17 @@
+use bevy::prelude::*;
+
+use super::POWER_UP_SPAWN_TIME;
+
+
+#[derive(Resource)]
+pub struct PowerUpSpawnTimer {
+    pub timer: Timer,
+}
+
+impl Default for PowerUpSpawnTimer {
+    fn default() -> Self {
+        Self {
+            timer: Timer::from_seconds(POWER_UP_SPAWN_TIME, TimerMode::Repeating),
+        }
+    }
+}
@@ -0,0 +1,214 @@
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+use rand::prelude::*;
+
+use crate::events::{PowerUpCollected, PowerUpExpired};
+use crate::game::enemy::components::{Enemy, EnemyKind, SpawnWarning};
+use crate::game::enemy::resources::EnemySpawnConfig;
+use crate::game::enemy::systems::kind_of;
+use crate::game::player::components::Player;
+use crate::game::player::PLAYER_SIZE;
+use crate::game::star::components::Star;
+use crate::state_scoped::StateScoped;
+use crate::AppState;
+
+use super::components::*;
+use super::resources::*;
+use super::{BOMB_RADIUS, MAGNET_RADIUS, MAGNET_SPEED, POWER_UP_PICKUP_TIME, POWER_UP_SIZE};
+
+
+pub fn reset_power_up_spawn_timer(mut power_up_spawn_timer: ResMut<PowerUpSpawnTimer>) {
+    power_up_spawn_timer.timer.reset();
+}
+
+pub fn tick_power_up_spawn_timer(mut power_up_spawn_timer: ResMut<PowerUpSpawnTimer>, time: Res<Time>) {
+    power_up_spawn_timer.timer.tick(time.delta());
+}
+
+pub fn spawn_power_ups_over_time(
+    mut commands: Commands,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    asset_server: Res<AssetServer>,
+    power_up_spawn_timer: Res<PowerUpSpawnTimer>,
+    spawn_config: Res<EnemySpawnConfig>,
+) {
+    if !power_up_spawn_timer.timer.finished() {
+        return;
+    }
+
+    let window = window_query.get_single().unwrap();
+    let window_size = Vec2::new(window.width(), window.height());
+    let mut rng = thread_rng();
+    // No players to keep clear of; this just keeps pickups off the very edge.
+    let position = spawn_config.find_position(window_size, &[], &mut rng);
+    let kind = *PowerUpKind::ALL.choose(&mut rng).unwrap();
+
+    commands
+        .spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: kind.tint(),
+                    custom_size: Some(Vec2::splat(POWER_UP_SIZE)),
+                    ..default()
+                },
+                transform: Transform::from_translation(position.extend(0.0)),
+                texture: asset_server.load("sprites/ball_white_large.png"),
+                ..default()
+            },
+            PowerUp {
+                kind,
+                timer: Timer::from_seconds(POWER_UP_PICKUP_TIME, TimerMode::Once),
+            },
+            StateScoped(AppState::Game),
+        ))
+        .with_children(|parent| {
+            parent.spawn(Text2dBundle {
+                text: Text::from_section(
+                    kind.icon(),
+                    TextStyle {
+                        font_size: POWER_UP_SIZE * 0.6,
+                        color: Color::BLACK,
+                        ..default()
+                    },
+                ),
+                transform: Transform::from_xyz(0.0, 0.0, 1.0),
+                ..default()
+            });
+        });
+}
+
+pub fn expire_power_up_pickups(
+    mut commands: Commands,
+    mut power_up_query: Query<(Entity, &mut PowerUp)>,
+    time: Res<Time>,
+) {
+    for (power_up_entity, mut power_up) in power_up_query.iter_mut() {
+        power_up.timer.tick(time.delta());
+
+        if power_up.timer.finished() {
+            commands.entity(power_up_entity).despawn_recursive();
+        }
+    }
+}
+
+pub fn collect_power_ups(
+    mut commands: Commands,
+    power_up_query: Query<(Entity, &Transform, &PowerUp)>,
+    mut player_query: Query<(&Player, &Transform, &mut PowerUpEffects)>,
+    enemy_query: Query<(Entity, &Transform, AnyOf<(&Enemy, &SpawnWarning)>)>,
+    asset_server: Res<AssetServer>,
+    mut power_up_collected_event_writer: EventWriter<PowerUpCollected>,
+) {
+    for (power_up_entity, power_up_transform, power_up) in power_up_query.iter() {
+        let collector = player_query.iter_mut().find(|(_, player_transform, effects)| {
+            player_transform
+                .translation
+                .distance(power_up_transform.translation)
+                < PLAYER_SIZE / 2.0 * effects.size_scale() + POWER_UP_SIZE / 2.0
+        });
+        let Some((player, player_transform, mut effects)) = collector else {
+            continue;
+        };
+
+        commands.entity(power_up_entity).despawn_recursive();
+        power_up_collected_event_writer.send(PowerUpCollected {
+            player: player.index,
+            kind: power_up.kind,
+        });
+
+        if power_up.kind == PowerUpKind::Bomb {
+            // Bosses shrug bombs off; everything else nearby, warnings included, is gone.
+            for (enemy_entity, enemy_transform, enemy) in enemy_query.iter() {
+                if kind_of(enemy) != EnemyKind::Boss
+                    && enemy_transform.translation.distance(player_transform.translation) <= BOMB_RADIUS
+                {
+                    commands.entity(enemy_entity).despawn_recursive();
+                }
+            }
+            commands.spawn(AudioBundle {
+                source: asset_server.load("audio/explosionCrunch_000.ogg"),
+                settings: PlaybackSettings::DESPAWN,
+            });
+        } else {
+            effects.apply(power_up.kind);
+            commands.spawn(AudioBundle {
+                source: asset_server.load("audio/pluck_001.ogg"),
+                settings: PlaybackSettings::DESPAWN,
+            });
+        }
+    }
+}
+
+pub fn tick_power_up_effects(
+    mut player_query: Query<(&Player, &mut PowerUpEffects)>,
+    mut power_up_expired_event_writer: EventWriter<PowerUpExpired>,
+    time: Res<Time>,
+) {
+    let is_over = |effect: &ActiveEffect| {
+        let used_up = effect.kind == PowerUpKind::Shield && effect.charges == 0;
+        effect.timer.finished() || used_up
+    };
+
+    for (player, mut effects) in player_query.iter_mut() {
+        // Ticking only counts as a change when a countdown passes a whole second, which is all
+        // the HUD shows of it.
+        let mut second_passed = false;
+        for effect in effects.bypass_change_detection().active.iter_mut() {
+            let seconds_left = effect.timer.remaining_secs().ceil();
+            effect.timer.tick(time.delta());
+            second_passed |= effect.timer.remaining_secs().ceil() != seconds_left;
+        }
+        if second_passed {
+            effects.set_changed();
+        }
+
+        if !effects.active.iter().any(is_over) {
+            continue;
+        }
+        effects.active.retain(|effect| {
+            if is_over(effect) {
+                power_up_expired_event_writer.send(PowerUpExpired {
+                    player: player.index,
+                    kind: effect.kind,
+                });
+                return false;
+            }
+            true
+        });
+    }
+}
+
+pub fn shrink_players(mut player_query: Query<(&mut Transform, &PowerUpEffects), With<Player>>) {
+    for (mut transform, effects) in player_query.iter_mut() {
+        let scale = effects.size_scale();
+
+        if transform.scale.x != scale {
+            transform.scale = Vec3::splat(scale);
+        }
+    }
+}
+
+pub fn pull_stars(
+    player_query: Query<(&Transform, &PowerUpEffects), With<Player>>,
+    mut star_query: Query<&mut Transform, (With<Star>, Without<Player>)>,
+    time: Res<Time>,
+) {
+    let step = MAGNET_SPEED * time.delta_seconds();
+
+    for (player_transform, effects) in player_query.iter() {
+        if !effects.is_active(PowerUpKind::Magnet) {
+            continue;
+        }
+
+        for mut star_transform in star_query.iter_mut() {
+            let offset = (player_transform.translation - star_transform.translation).truncate();
+            let distance = offset.length();
+
+            if distance <= MAGNET_RADIUS && distance > 0.0 {
+                let pull = offset / distance * step.min(distance);
+                star_transform.translation += pull.extend(0.0);
+            }
+        }
+    }
+}
@@ -0,0 +1,37 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy::time::Stopwatch;
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+
+/// How long the current run has been going, not counting pauses.
+#[derive(Resource, Default)]
+pub struct RunTime {
+    pub stopwatch: Stopwatch,
+}
+
+/// Kinds to draw at random, each in proportion to its weight, e.g. `EnemySpawnTable`.
+/// Tables authored as data load as assets and are copied into the resource.
+#[derive(Resource, Asset, TypePath, Debug, Clone, Serialize, Deserialize)]
+pub struct WeightedTable<K: TypePath + Send + Sync + 'static> {
+    pub weights: Vec<(K, f32)>,
+}
+
+impl<K: Copy + Default + TypePath + Send + Sync + 'static> WeightedTable<K> {
+    /// Picks a kind in proportion to its weight. Falls back to `K::default()` if no weight is positive.
+    pub fn choose(&self, rng: &mut impl Rng) -> K {
+        match WeightedIndex::new(self.weights.iter().map(|(_, weight)| weight.max(0.0))) {
+            Ok(index) => self.weights[index.sample(rng)].0,
+            Err(_) => K::default(),
+        }
+    }
+}
+
+/// Formats a run length as `m:ss`.
+pub fn format_run_time(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
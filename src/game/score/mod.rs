@@ -3,18 +3,45 @@ use bevy::prelude::*;
 
 
 pub mod resources;
+mod storage;
 mod systems;
 
+use crate::{events::HighScoreEntered, AppState};
+
+use super::{RunRestartSet, SimulationState};
+
 use self::{resources::*, systems::*};
 
+/// Seconds to pick up the second star of a combo; each star after that leaves a bit less.
+pub const COMBO_WINDOW: f32 = 2.5;
+pub const COMBO_WINDOW_STEP: f32 = 0.1;
+pub const MIN_COMBO_WINDOW: f32 = 0.8;
+/// Stars per multiplier step.
+pub const COMBO_STEP: u32 = 3;
+pub const MAX_COMBO_MULTIPLIER: u32 = 5;
+
 pub struct ScorePlugin;
 
 impl Plugin for ScorePlugin {
     fn build(&self, app: &mut App) {
         app
         .init_resource::<Score>()
-        .add_systems(Update, update_score)
-        
+        .init_resource::<ComboWindow>()
+        .init_resource::<HighScores>()
+        .init_resource::<HighScoresConfig>()
+        .add_event::<HighScoreEntered>()
+        .add_systems(Startup, load_high_scores_on_startup)
+        .add_systems(OnEnter(AppState::Game), reset_score)
+        .add_systems(Update, reset_score.in_set(RunRestartSet::Setup))
+        .add_systems(Update, update_high_scores.run_if(in_state(AppState::Game)))
+        .add_systems(
+            Update,
+            tick_combo_window
+                .run_if(in_state(AppState::Game))
+                .run_if(in_state(SimulationState::Running)),
+        )
+        .add_systems(Update, (record_high_score, high_scores_updated).chain())
+
         ;
     }
-}
\ No newline at end of file
+}
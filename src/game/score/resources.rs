@@ -1,17 +1,245 @@
+use std::path::PathBuf;
+
 use bevy::prelude::*;
 
+use crate::persistence::data_dir;
+
+use super::{COMBO_STEP, COMBO_WINDOW, COMBO_WINDOW_STEP, MAX_COMBO_MULTIPLIER, MIN_COMBO_WINDOW};
+
+pub const HIGH_SCORES_FILE: &str = "high_scores.ron";
+pub const MAX_HIGH_SCORES: usize = 10;
+pub const DEFAULT_PLAYER_NAME: &str = "Player";
+
 
+/// The run's score. `value` is the team total; `players` splits it by player index.
+/// The combo is shared by the whole team.
 #[derive(Resource)]
-#[derive(Default)]
 pub struct Score {
     pub value: u32,
+    pub players: Vec<u32>,
+    /// Points as they would have been without any combo multiplier.
+    pub raw: u32,
+    /// Stars picked up in the current combo.
+    pub combo: u32,
+    pub multiplier: u32,
+    pub best_combo: u32,
+}
+
+impl Default for Score {
+    fn default() -> Self {
+        Self {
+            value: 0,
+            players: Vec::new(),
+            raw: 0,
+            combo: 0,
+            multiplier: 1,
+            best_combo: 0,
+        }
+    }
+}
+
+impl Score {
+    /// Adds points as they are, outside of the combo.
+    pub fn add(&mut self, player: usize, points: u32) {
+        self.raw += points;
+        self.credit(player, points);
+    }
+
+    /// Counts a pickup towards the combo, then adds `points` times the multiplier it leaves.
+    /// Returns the points actually added.
+    pub fn add_combo(&mut self, player: usize, points: u32) -> u32 {
+        self.combo += 1;
+        self.best_combo = self.best_combo.max(self.combo);
+        self.multiplier = (1 + (self.combo - 1) / COMBO_STEP).min(MAX_COMBO_MULTIPLIER);
+
+        let awarded = points * self.multiplier;
+        self.raw += points;
+        self.credit(player, awarded);
+        awarded
+    }
+
+    pub fn break_combo(&mut self) {
+        self.combo = 0;
+        self.multiplier = 1;
+    }
+
+    fn credit(&mut self, player: usize, points: u32) {
+        if self.players.len() <= player {
+            self.players.resize(player + 1, 0);
+        }
+        self.players[player] += points;
+        self.value += points;
+    }
+
+    pub fn of_player(&self, player: usize) -> u32 {
+        self.players.get(player).copied().unwrap_or_default()
+    }
 }
 
 
 
+/// Time left to pick up another star before the combo breaks.
+#[derive(Resource, Default)]
+pub struct ComboWindow {
+    pub timer: Timer,
+}
+
+impl ComboWindow {
+    /// Starts the window over, a little shorter the longer `combo` already is.
+    pub fn restart(&mut self, combo: u32) {
+        let seconds = COMBO_WINDOW - COMBO_WINDOW_STEP * combo.saturating_sub(1) as f32;
+        self.timer = Timer::from_seconds(seconds.max(MIN_COMBO_WINDOW), TimerMode::Once);
+    }
+}
+
+/// High-score table, kept sorted from best to worst.
 #[derive(Resource, Debug)]
 #[derive(Default)]
 pub struct HighScores {
     pub scores: Vec<(String, u32)>,
+    /// Name entered for the most recent high score, offered again next time.
+    pub last_name: Option<String>,
+}
+
+impl HighScores {
+    pub fn default_name(&self) -> &str {
+        self.last_name.as_deref().unwrap_or(DEFAULT_PLAYER_NAME)
+    }
+
+    /// Whether `score` would make it into a table of at most `max_entries`.
+    pub fn qualifies(&self, score: u32, max_entries: usize) -> bool {
+        if score == 0 || max_entries == 0 {
+            return false;
+        }
+
+        self.scores.len() < max_entries
+            || self.scores.last().is_some_and(|(_, lowest)| score > *lowest)
+    }
+
+    /// Zero-based position `score` would take. Ties go below the existing entries.
+    pub fn rank_of(&self, score: u32) -> usize {
+        self.scores
+            .iter()
+            .position(|(_, existing)| score > *existing)
+            .unwrap_or(self.scores.len())
+    }
+
+    /// Inserts a qualifying score and drops whatever falls off the bottom of the table.
+    /// Returns the zero-based rank of the new entry.
+    pub fn insert(&mut self, name: String, score: u32, max_entries: usize) -> Option<usize> {
+        if !self.qualifies(score, max_entries) {
+            return None;
+        }
+
+        let rank = self.rank_of(score);
+        self.scores.insert(rank, (name, score));
+        self.scores.truncate(max_entries);
+        Some(rank)
+    }
+
+    /// Restores the sorted, truncated invariant after loading entries from disk.
+    pub fn normalize(&mut self, max_entries: usize) {
+        // Stable sort so entries with equal scores keep their saved order.
+        self.scores.sort_by(|(_, a), (_, b)| b.cmp(a));
+        self.scores.truncate(max_entries);
+    }
 }
 
+
+/// Score of the run that just ended, waiting for the player to enter a name.
+#[derive(Resource, Debug)]
+pub struct PendingHighScore {
+    pub score: u32,
+    /// Zero-based position the score will take in the table.
+    pub rank: usize,
+}
+
+
+#[derive(Resource, Debug)]
+pub struct HighScoresConfig {
+    pub max_entries: usize,
+    pub path: PathBuf,
+}
+
+impl Default for HighScoresConfig {
+    fn default() -> Self {
+        Self {
+            max_entries: MAX_HIGH_SCORES,
+            path: data_dir().join(HIGH_SCORES_FILE),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn multiplier_steps_up_every_few_stars() {
+        let mut score = Score::default();
+
+        for _ in 0..COMBO_STEP {
+            assert_eq!(score.add_combo(0, 1), 1);
+        }
+        assert_eq!(score.add_combo(0, 1), 2);
+        assert_eq!(score.multiplier, 2);
+        assert_eq!(score.combo, COMBO_STEP + 1);
+    }
+
+    #[test]
+    fn multiplier_is_capped() {
+        let mut score = Score::default();
+
+        for _ in 0..COMBO_STEP * MAX_COMBO_MULTIPLIER * 2 {
+            score.add_combo(0, 1);
+        }
+
+        assert_eq!(score.multiplier, MAX_COMBO_MULTIPLIER);
+        assert_eq!(score.add_combo(0, 3), 3 * MAX_COMBO_MULTIPLIER);
+    }
+
+    #[test]
+    fn add_combo_credits_the_player_and_tracks_raw_points() {
+        let mut score = Score::default();
+
+        for _ in 0..COMBO_STEP {
+            score.add_combo(0, 2);
+        }
+        let awarded = score.add_combo(1, 2);
+
+        assert_eq!(score.raw, 2 * (COMBO_STEP + 1));
+        assert_eq!(score.of_player(0), 2 * COMBO_STEP);
+        assert_eq!(score.of_player(1), awarded);
+        assert_eq!(score.value, score.of_player(0) + score.of_player(1));
+    }
+
+    #[test]
+    fn breaking_the_combo_keeps_the_best() {
+        let mut score = Score::default();
+        for _ in 0..COMBO_STEP + 1 {
+            score.add_combo(0, 1);
+        }
+
+        score.break_combo();
+        score.add_combo(0, 1);
+
+        assert_eq!(score.combo, 1);
+        assert_eq!(score.multiplier, 1);
+        assert_eq!(score.best_combo, COMBO_STEP + 1);
+    }
+
+    #[test]
+    fn combo_window_shrinks_down_to_the_minimum() {
+        let mut combo_window = ComboWindow::default();
+
+        combo_window.restart(1);
+        assert_eq!(combo_window.timer.duration().as_secs_f32(), COMBO_WINDOW);
+
+        combo_window.restart(2);
+        assert!(combo_window.timer.duration().as_secs_f32() < COMBO_WINDOW);
+
+        combo_window.restart(1000);
+        assert_eq!(combo_window.timer.duration().as_secs_f32(), MIN_COMBO_WINDOW);
+        assert!(!combo_window.timer.finished());
+    }
+}
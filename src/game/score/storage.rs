@@ -0,0 +1,157 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use bevy::prelude::*;
+use ron::ser::PrettyConfig;
+use serde::{Deserialize, Serialize};
+
+use crate::persistence::{back_up, write_atomically};
+
+use super::resources::HighScores;
+
+/// Bump whenever `HighScoresFile` changes shape, and teach `parse_high_scores` to read the
+/// previous layout so existing tables get migrated instead of discarded.
+pub const HIGH_SCORES_VERSION: u32 = 2;
+
+#[derive(Serialize, Deserialize)]
+struct HighScoresFile {
+    version: u32,
+    last_name: Option<String>,
+    scores: Vec<HighScoreEntry>,
+}
+
+/// Version 1 had no remembered name.
+#[derive(Deserialize)]
+struct HighScoresFileV1 {
+    scores: Vec<HighScoreEntry>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct HighScoreEntry {
+    name: String,
+    score: u32,
+}
+
+/// Just the version field, read first to find out which layout the rest of the file uses.
+#[derive(Deserialize)]
+struct VersionHeader {
+    version: u32,
+}
+
+/// Reads the high-score table at `path`.
+///
+/// A missing file yields an empty table. Files from older versions are backed up and
+/// rewritten in the current format; unreadable files are backed up and replaced by an
+/// empty table.
+pub fn load_high_scores(path: &Path) -> HighScores {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return HighScores::default(),
+        Err(error) => {
+            warn!("Could not read high scores from {}: {}", path.display(), error);
+            return HighScores::default();
+        }
+    };
+
+    match parse_high_scores(&contents) {
+        Ok((high_scores, HIGH_SCORES_VERSION)) => high_scores,
+        Ok((high_scores, version)) => {
+            info!(
+                "Migrating high scores in {} from version {} to {}",
+                path.display(),
+                version,
+                HIGH_SCORES_VERSION
+            );
+            if let Err(error) = back_up(path).and_then(|_| save_high_scores(&high_scores, path)) {
+                warn!("Could not migrate high scores in {}: {}", path.display(), error);
+            }
+            high_scores
+        }
+        Err(reason) => {
+            match back_up(path) {
+                Ok(backup_path) => warn!(
+                    "High scores in {} are unreadable ({}), moved to {}",
+                    path.display(),
+                    reason,
+                    backup_path.display()
+                ),
+                Err(error) => warn!(
+                    "High scores in {} are unreadable ({}) and could not be backed up: {}",
+                    path.display(),
+                    reason,
+                    error
+                ),
+            }
+            HighScores::default()
+        }
+    }
+}
+
+pub fn save_high_scores(high_scores: &HighScores, path: &Path) -> io::Result<()> {
+    let file = HighScoresFile {
+        version: HIGH_SCORES_VERSION,
+        last_name: high_scores.last_name.clone(),
+        scores: high_scores
+            .scores
+            .iter()
+            .map(|(name, score)| HighScoreEntry {
+                name: name.clone(),
+                score: *score,
+            })
+            .collect(),
+    };
+
+    let contents = ron::ser::to_string_pretty(&file, PrettyConfig::default())
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+    write_atomically(path, &contents)
+}
+
+/// Returns the table together with the version it was stored as.
+fn parse_high_scores(contents: &str) -> Result<(HighScores, u32), String> {
+    if let Ok(header) = ron::from_str::<VersionHeader>(contents) {
+        return match header.version {
+            HIGH_SCORES_VERSION => {
+                let file = ron::from_str::<HighScoresFile>(contents).map_err(|error| error.to_string())?;
+                Ok((
+                    HighScores {
+                        scores: into_scores(file.scores),
+                        last_name: file.last_name,
+                    },
+                    HIGH_SCORES_VERSION,
+                ))
+            }
+            1 => {
+                let file = ron::from_str::<HighScoresFileV1>(contents).map_err(|error| error.to_string())?;
+                Ok((
+                    HighScores {
+                        scores: into_scores(file.scores),
+                        last_name: None,
+                    },
+                    1,
+                ))
+            }
+            version => Err(format!("unsupported version {}", version)),
+        };
+    }
+
+    // Version 0 was the bare `(name, score)` list `HighScores` keeps in memory.
+    ron::from_str::<Vec<(String, u32)>>(contents)
+        .map(|scores| {
+            (
+                HighScores {
+                    scores,
+                    last_name: None,
+                },
+                0,
+            )
+        })
+        .map_err(|error| error.to_string())
+}
+
+fn into_scores(entries: Vec<HighScoreEntry>) -> Vec<(String, u32)> {
+    entries
+        .into_iter()
+        .map(|entry| (entry.name, entry.score))
+        .collect()
+}
@@ -2,20 +2,72 @@ use bevy::prelude::*;
 
 use crate::events::*;
 use super::resources::*;
+use super::storage::*;
 
 
-pub fn update_score(score: Res<Score>) {
-    if score.is_changed() {
-        println!("Score - {}", score.value)
+pub fn load_high_scores_on_startup(mut commands: Commands, config: Res<HighScoresConfig>) {
+    let mut high_scores = load_high_scores(&config.path);
+    high_scores.normalize(config.max_entries);
+    commands.insert_resource(high_scores);
+}
+
+pub fn reset_score(mut score: ResMut<Score>, mut combo_window: ResMut<ComboWindow>) {
+    *score = Score::default();
+    *combo_window = ComboWindow::default();
+}
+
+pub fn tick_combo_window(
+    mut score: ResMut<Score>,
+    mut combo_window: ResMut<ComboWindow>,
+    time: Res<Time>,
+) {
+    if score.combo == 0 {
+        return;
+    }
+
+    combo_window.timer.tick(time.delta());
+    if combo_window.timer.finished() {
+        score.break_combo();
     }
 }
 
 pub fn update_high_scores(
+    mut commands: Commands,
     mut game_over_event_reader: EventReader<GameOver>,
+    high_scores: Res<HighScores>,
+    config: Res<HighScoresConfig>,
+) {
+    let Some(score) = game_over_event_reader.read().map(|event| event.score).max() else {
+        return;
+    };
+
+    if high_scores.qualifies(score, config.max_entries) {
+        commands.insert_resource(PendingHighScore {
+            score,
+            rank: high_scores.rank_of(score),
+        });
+    }
+}
+
+pub fn record_high_score(
+    mut commands: Commands,
+    mut high_score_entered_event_reader: EventReader<HighScoreEntered>,
     mut high_scores: ResMut<HighScores>,
+    config: Res<HighScoresConfig>,
 ) {
-    for event in game_over_event_reader.read() {
-        high_scores.scores.push(("Player".to_string(), event.score));
+    let mut table_changed = false;
+
+    for event in high_score_entered_event_reader.read() {
+        high_scores.insert(event.name.clone(), event.score, config.max_entries);
+        high_scores.last_name = Some(event.name.clone());
+        table_changed = true;
+    }
+
+    if table_changed {
+        commands.remove_resource::<PendingHighScore>();
+        if let Err(error) = save_high_scores(&high_scores, &config.path) {
+            error!("Could not save high scores to {}: {}", config.path.display(), error);
+        }
     }
 }
 
@@ -23,4 +75,4 @@ pub fn high_scores_updated(high_scores: Res<HighScores>) {
     if high_scores.is_changed() {
         println!("High Scores: {:?}", high_scores);
     }
-}
\ No newline at end of file
+}
@@ -1,6 +1,99 @@
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
+use super::{STAR_DRIFT_SPEED, STAR_LIFETIME};
 
 
 #[derive(Component)]
-pub struct Star {}
+pub struct Star {
+    pub kind: StarKind,
+    /// Points for picking it up.
+    pub value: u32,
+}
+
+#[derive(TypePath, Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum StarKind {
+    /// Worth a point and stays put until collected.
+    #[default]
+    Plain,
+    /// Worth a lot, but gone again quickly.
+    Gold,
+    /// Worth a little more, but fades away after a while.
+    Decaying,
+    /// Floats around the arena, bouncing off the edges.
+    Drifting,
+}
+
+/// Fixed stats of a star kind.
+pub struct StarArchetype {
+    pub value: u32,
+    /// Multiplied onto the greyscale `star_white.png`; plain stars keep the old yellow.
+    pub tint: Color,
+    /// Seconds before it disappears, if it ever does.
+    pub lifetime: Option<f32>,
+    pub drift_speed: Option<f32>,
+    /// Relative chance of being picked by the loot table.
+    pub weight: f32,
+}
+
+impl StarKind {
+    pub const ALL: [StarKind; 4] = [
+        StarKind::Plain,
+        StarKind::Gold,
+        StarKind::Decaying,
+        StarKind::Drifting,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            StarKind::Plain => "plain",
+            StarKind::Gold => "gold",
+            StarKind::Decaying => "decaying",
+            StarKind::Drifting => "drifting",
+        }
+    }
+
+    pub fn archetype(self) -> StarArchetype {
+        match self {
+            StarKind::Plain => StarArchetype {
+                value: 1,
+                tint: Color::rgb(1.0, 0.93, 0.15),
+                lifetime: None,
+                drift_speed: None,
+                weight: 6.0,
+            },
+            StarKind::Gold => StarArchetype {
+                value: 5,
+                tint: Color::rgb(1.0, 0.55, 0.1),
+                lifetime: Some(STAR_LIFETIME / 2.0),
+                drift_speed: None,
+                weight: 1.0,
+            },
+            StarKind::Decaying => StarArchetype {
+                value: 2,
+                tint: Color::rgb(0.35, 0.7, 1.0),
+                lifetime: Some(STAR_LIFETIME),
+                drift_speed: None,
+                weight: 2.0,
+            },
+            StarKind::Drifting => StarArchetype {
+                value: 2,
+                tint: Color::rgb(0.4, 1.0, 0.4),
+                lifetime: None,
+                drift_speed: Some(STAR_DRIFT_SPEED),
+                weight: 2.0,
+            },
+        }
+    }
+}
+
+/// Counts down to the star disappearing. It blinks for the last few seconds as a warning.
+#[derive(Component)]
+pub struct StarLifetime {
+    pub timer: Timer,
+}
+
+#[derive(Component)]
+pub struct Drift {
+    pub velocity: Vec2,
+}
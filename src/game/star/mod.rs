@@ -8,24 +8,41 @@ use crate::AppState;
 
 use self::{resources::*, systems::*};
 
-use super::SimulationState;
+use super::loader::RonAssetLoader;
+use super::{RunRestartSet, SimulationState};
 
 pub const STARS_NUMBER: usize = 10;
 pub const STAR_SIZE: f32 = 30.0;
+/// How long decaying stars last; gold ones get half of it.
+pub const STAR_LIFETIME: f32 = 12.0;
+/// Stars about to expire blink for this long first.
+pub const STAR_BLINK_TIME: f32 = 3.0;
+pub const STAR_BLINK_INTERVAL: f32 = 0.15;
+pub const STAR_DRIFT_SPEED: f32 = 60.0;
+/// Named with the `.loot.ron` extension so only the loot loader picks it up.
+pub const STAR_LOOT_FILE: &str = "game.loot.ron";
 
 pub struct StarPlugin;
 
 impl Plugin for StarPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<StarSpawnTimer>()
-            .add_systems(OnEnter(AppState::Game), spawn_stars)
+            .init_resource::<StarLootTable>()
+            .init_asset::<StarLootTable>()
+            .init_asset_loader::<RonAssetLoader<StarLootTable>>()
+            .add_systems(Startup, load_star_loot)
+            .add_systems(Update, apply_star_loot.run_if(on_event::<AssetEvent<StarLootTable>>()))
+            .add_systems(OnEnter(AppState::Game), (spawn_stars, reset_star_spawn_timer))
             .add_systems(
                 Update,
-                (tick_star_spawn_timer, spawn_stars_over_time)
+                (spawn_stars, reset_star_spawn_timer).in_set(RunRestartSet::Setup),
+            )
+            .add_systems(
+                Update,
+                (tick_star_spawn_timer, spawn_stars_over_time, expire_stars, drift_stars)
                     .run_if(in_state(AppState::Game))
                     .run_if(in_state(SimulationState::Running)),
             )
-            .add_systems(OnExit(AppState::Game), despawn_stars)
             ;
     }
 }
@@ -1,5 +1,10 @@
 use bevy::prelude::*;
 
+use crate::game::loader::RonAsset;
+use crate::game::resources::WeightedTable;
+
+use super::components::StarKind;
+
 pub const STAR_SPAWN_TIME: f32 = 10.0;
 
 
@@ -16,3 +21,36 @@ impl Default for StarSpawnTimer {
     }
 }
 
+/// Weighted table new stars' kinds are drawn from. Loaded from `STAR_LOOT_FILE`.
+pub type StarLootTable = WeightedTable<StarKind>;
+
+impl RonAsset for StarLootTable {
+    const EXTENSIONS: &'static [&'static str] = &["loot.ron"];
+}
+
+/// Keeps `STAR_LOOT_FILE` loaded for the whole session.
+#[derive(Resource)]
+pub struct StarLootTableHandle(pub Handle<StarLootTable>);
+
+impl Default for StarLootTable {
+    fn default() -> Self {
+        Self {
+            weights: StarKind::ALL
+                .into_iter()
+                .map(|kind| (kind, kind.archetype().weight))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundled_loot_matches_archetypes() {
+        let loot: StarLootTable = ron::from_str(include_str!("../../../assets/game.loot.ron")).unwrap();
+
+        assert_eq!(loot.weights, StarLootTable::default().weights);
+    }
+}
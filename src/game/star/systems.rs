@@ -3,39 +3,95 @@ use bevy::window::PrimaryWindow;
 
 use rand::prelude::*;
 
+use crate::state_scoped::StateScoped;
+use crate::AppState;
+
 use super::components::*;
 use super::resources::*;
-use super::STARS_NUMBER;
+use super::{STARS_NUMBER, STAR_BLINK_INTERVAL, STAR_BLINK_TIME, STAR_LOOT_FILE, STAR_SIZE};
+
+pub fn load_star_loot(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(StarLootTableHandle(asset_server.load(STAR_LOOT_FILE)));
+}
+
+/// Swaps in the weights from `STAR_LOOT_FILE` once they load, and again whenever they're edited.
+/// Until then, or if the file fails to load, the archetype weights are used.
+pub fn apply_star_loot(
+    mut asset_event_reader: EventReader<AssetEvent<StarLootTable>>,
+    loot_handle: Res<StarLootTableHandle>,
+    loot_tables: Res<Assets<StarLootTable>>,
+    mut loot_table: ResMut<StarLootTable>,
+) {
+    let id = loot_handle.0.id();
+    if !asset_event_reader
+        .read()
+        .any(|event| event.is_loaded_with_dependencies(id) || event.is_modified(id))
+    {
+        return;
+    }
+
+    if let Some(loaded) = loot_tables.get(id) {
+        *loot_table = loaded.clone();
+    }
+}
 
 pub fn spawn_stars(
     mut commands: Commands,
     window_query: Query<&Window, With<PrimaryWindow>>,
     asset_server: Res<AssetServer>,
+    loot_table: Res<StarLootTable>,
 ) {
     let window = window_query.get_single().unwrap();
-    println!("Spawning star spawn_stars spawn_stars");
+    let mut rng = thread_rng();
 
     for _ in 0..STARS_NUMBER {
         let rand_x = random::<f32>() * window.width();
         let rand_y = random::<f32>() * window.height();
-        commands.spawn((
-            SpriteBundle {
-                transform: Transform::from_xyz(rand_x, rand_y, 0.0),
-                texture: asset_server.load("sprites/star.png"),
+        let kind = loot_table.choose(&mut rng);
+        spawn_star_of_kind(&mut commands, &asset_server, kind, Vec2::new(rand_x, rand_y));
+    }
+}
+
+/// Spawns a `kind` star at `position`, drifting off in a random direction if its kind drifts.
+pub fn spawn_star_of_kind(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    kind: StarKind,
+    position: Vec2,
+) {
+    let archetype = kind.archetype();
+
+    let mut star = commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: archetype.tint,
                 ..default()
             },
-            Star {},
-        ));
+            transform: Transform::from_translation(position.extend(0.0)),
+            texture: asset_server.load("sprites/star_white.png"),
+            ..default()
+        },
+        Star {
+            kind,
+            value: archetype.value,
+        },
+        StateScoped(AppState::Game),
+    ));
+
+    if let Some(lifetime) = archetype.lifetime {
+        star.insert(StarLifetime {
+            timer: Timer::from_seconds(lifetime, TimerMode::Once),
+        });
+    }
+    if let Some(drift_speed) = archetype.drift_speed {
+        star.insert(Drift {
+            velocity: Vec2::from_angle(random::<f32>() * std::f32::consts::TAU) * drift_speed,
+        });
     }
 }
 
-pub fn despawn_stars(
-    mut commands: Commands,
-    stars_query: Query<Entity, With<Star>>
-) {
-  for star_entity in stars_query.iter() {
-    commands.entity(star_entity).despawn();
-  }
+pub fn reset_star_spawn_timer(mut star_spawn_timer: ResMut<StarSpawnTimer>) {
+    star_spawn_timer.timer.reset();
 }
 
 pub fn tick_star_spawn_timer(mut star_spawn_timer: ResMut<StarSpawnTimer>, time: Res<Time>) {
@@ -47,20 +103,60 @@ pub fn spawn_stars_over_time(
     window_query: Query<&Window, With<PrimaryWindow>>,
     asset_server: Res<AssetServer>,
     star_spawn_timer: ResMut<StarSpawnTimer>,
+    loot_table: Res<StarLootTable>,
 ) {
     if star_spawn_timer.timer.finished() {
         let window = window_query.get_single().unwrap();
 
         let rand_x = window.width() * random::<f32>();
         let rand_y = window.height() * random::<f32>();
-        println!("Spawning star");
-        commands.spawn((
-            SpriteBundle {
-                transform: Transform::from_xyz(rand_x, rand_y, 0.0),
-                texture: asset_server.load("sprites/star.png"),
-                ..default()
-            },
-            Star {},
-        ));
+        let kind = loot_table.choose(&mut thread_rng());
+        spawn_star_of_kind(&mut commands, &asset_server, kind, Vec2::new(rand_x, rand_y));
+    }
+}
+
+pub fn expire_stars(
+    mut commands: Commands,
+    mut star_query: Query<(Entity, &mut StarLifetime, &mut Visibility), With<Star>>,
+    time: Res<Time>,
+) {
+    for (star_entity, mut lifetime, mut visibility) in star_query.iter_mut() {
+        lifetime.timer.tick(time.delta());
+
+        if lifetime.timer.finished() {
+            commands.entity(star_entity).despawn();
+            continue;
+        }
+
+        let remaining = lifetime.timer.remaining_secs();
+        let blink = remaining < STAR_BLINK_TIME && (remaining / STAR_BLINK_INTERVAL) as u32 % 2 == 0;
+        *visibility = if blink { Visibility::Hidden } else { Visibility::Inherited };
+    }
+}
+
+/// Moves drifting stars and bounces them off the window edges.
+pub fn drift_stars(
+    mut star_query: Query<(&mut Transform, &mut Drift), With<Star>>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    time: Res<Time>,
+) {
+    let window = window_query.get_single().unwrap();
+    let half_star_size = STAR_SIZE / 2.0;
+    let min = Vec2::splat(half_star_size);
+    let max = Vec2::new(window.width(), window.height()) - half_star_size;
+
+    for (mut transform, mut drift) in star_query.iter_mut() {
+        let position = transform.translation.truncate() + drift.velocity * time.delta_seconds();
+
+        if (position.x <= min.x && drift.velocity.x < 0.0) || (position.x >= max.x && drift.velocity.x > 0.0) {
+            drift.velocity.x *= -1.0;
+        }
+        if (position.y <= min.y && drift.velocity.y < 0.0) || (position.y >= max.y && drift.velocity.y > 0.0) {
+            drift.velocity.y *= -1.0;
+        }
+
+        let position = position.clamp(min, max.max(min));
+        transform.translation.x = position.x;
+        transform.translation.y = position.y;
     }
 }
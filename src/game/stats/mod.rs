@@ -0,0 +1,35 @@
+use bevy::prelude::*;
+
+pub mod resources;
+mod systems;
+
+use crate::AppState;
+
+use self::{resources::*, systems::*};
+
+use super::{RunRestartSet, SimulationState};
+
+/// How much room an enemy can leave a player and still count as a near miss.
+pub const NEAR_MISS_MARGIN: f32 = 24.0;
+
+pub struct StatsPlugin;
+
+impl Plugin for StatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RunStats>()
+            .add_systems(OnEnter(AppState::Game), reset_run_stats)
+            .add_systems(Update, reset_run_stats.in_set(RunRestartSet::Setup))
+            .add_systems(
+                Update,
+                (count_stars_collected, count_enemies_spawned)
+                    .after(RunRestartSet::Restart)
+                    .run_if(in_state(AppState::Game)),
+            )
+            .add_systems(
+                Update,
+                (track_time_survived, track_distance_travelled, count_near_misses)
+                    .run_if(in_state(AppState::Game))
+                    .run_if(in_state(SimulationState::Running)),
+            );
+    }
+}
@@ -0,0 +1,59 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::game::enemy::components::EnemyKind;
+use crate::game::star::components::StarKind;
+
+
+/// Tallies for the current run, reset whenever one starts and handed to the game over screen.
+#[derive(Resource, Debug, Clone)]
+pub struct RunStats {
+    pub time_survived: Duration,
+    /// One entry per star kind, in `StarKind::ALL` order.
+    pub stars_collected: Vec<(StarKind, u32)>,
+    pub enemies_spawned: u32,
+    /// Enemies turning back off the window edges.
+    pub wall_bounces: u32,
+    /// Enemies that came within `NEAR_MISS_MARGIN` of a player and left without touching them.
+    pub near_misses: u32,
+    /// Summed over all players, in pixels.
+    pub distance_travelled: f32,
+    /// The hit that ended the run.
+    pub cause_of_death: Option<DeathCause>,
+}
+
+impl Default for RunStats {
+    fn default() -> Self {
+        Self {
+            time_survived: Duration::ZERO,
+            stars_collected: StarKind::ALL.into_iter().map(|kind| (kind, 0)).collect(),
+            enemies_spawned: 0,
+            wall_bounces: 0,
+            near_misses: 0,
+            distance_travelled: 0.0,
+            cause_of_death: None,
+        }
+    }
+}
+
+impl RunStats {
+    pub fn stars_of_kind(&self, kind: StarKind) -> u32 {
+        self.stars_collected
+            .iter()
+            .find(|(counted_kind, _)| *counted_kind == kind)
+            .map_or(0, |(_, count)| *count)
+    }
+
+    pub fn total_stars(&self) -> u32 {
+        self.stars_collected.iter().map(|(_, count)| count).sum()
+    }
+}
+
+/// Who took a player's last life, and where.
+#[derive(Debug, Clone)]
+pub struct DeathCause {
+    pub player: usize,
+    pub enemy: EnemyKind,
+    pub position: Vec2,
+}
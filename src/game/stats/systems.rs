@@ -0,0 +1,107 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+use crate::events::StarCollected;
+use crate::game::enemy::components::{Enemy, EnemyKind};
+use crate::game::player::components::Player;
+use crate::game::player::PLAYER_SIZE;
+use crate::game::resources::RunTime;
+
+use super::resources::*;
+use super::NEAR_MISS_MARGIN;
+
+
+pub fn reset_run_stats(mut run_stats: ResMut<RunStats>) {
+    *run_stats = RunStats::default();
+}
+
+pub fn track_time_survived(mut run_stats: ResMut<RunStats>, run_time: Res<RunTime>) {
+    run_stats.time_survived = run_time.stopwatch.elapsed();
+}
+
+pub fn count_stars_collected(
+    mut star_collected_event_reader: EventReader<StarCollected>,
+    mut run_stats: ResMut<RunStats>,
+) {
+    for event in star_collected_event_reader.read() {
+        if let Some((_, count)) = run_stats
+            .stars_collected
+            .iter_mut()
+            .find(|(kind, _)| *kind == event.kind)
+        {
+            *count += 1;
+        }
+    }
+}
+
+pub fn count_enemies_spawned(
+    added_enemy_query: Query<&Enemy, Added<Enemy>>,
+    mut run_stats: ResMut<RunStats>,
+) {
+    let spawned = added_enemy_query
+        .iter()
+        .filter(|enemy| enemy.kind != EnemyKind::Boss)
+        .count() as u32;
+    if spawned > 0 {
+        run_stats.enemies_spawned += spawned;
+    }
+}
+
+/// Sums how far each player moved since last frame. Players are new entities every run,
+/// so positions left over from a previous one are never matched.
+pub fn track_distance_travelled(
+    player_query: Query<(Entity, &Transform), With<Player>>,
+    mut last_positions: Local<HashMap<Entity, Vec2>>,
+    mut run_stats: ResMut<RunStats>,
+) {
+    let mut positions = HashMap::new();
+    let mut travelled = 0.0;
+
+    for (player_entity, transform) in player_query.iter() {
+        let position = transform.translation.truncate();
+        if let Some(last_position) = last_positions.get(&player_entity) {
+            travelled += last_position.distance(position);
+        }
+        positions.insert(player_entity, position);
+    }
+
+    *last_positions = positions;
+    if travelled > 0.0 {
+        run_stats.distance_travelled += travelled;
+    }
+}
+
+/// Follows every enemy that gets close to a player, and counts a near miss once it moves
+/// away again without ever touching. Pairs that end with either side gone don't count.
+pub fn count_near_misses(
+    player_query: Query<(Entity, &Transform), With<Player>>,
+    enemy_query: Query<(Entity, &Transform, &Enemy)>,
+    // Close (player, enemy) pairs, and whether they have touched.
+    mut close_pairs: Local<HashMap<(Entity, Entity), bool>>,
+    mut run_stats: ResMut<RunStats>,
+) {
+    let mut still_close = HashMap::new();
+
+    for (player_entity, player_transform) in player_query.iter() {
+        for (enemy_entity, enemy_transform, enemy) in enemy_query.iter() {
+            let distance = player_transform.translation.distance(enemy_transform.translation);
+            let touching_distance = PLAYER_SIZE / 2.0 + enemy.size / 2.0;
+            if distance >= touching_distance + NEAR_MISS_MARGIN {
+                continue;
+            }
+
+            let pair = (player_entity, enemy_entity);
+            let touched = close_pairs.get(&pair).copied().unwrap_or(false) || distance < touching_distance;
+            still_close.insert(pair, touched);
+        }
+    }
+
+    for ((player_entity, enemy_entity), touched) in close_pairs.iter() {
+        let gone = !player_query.contains(*player_entity) || !enemy_query.contains(*enemy_entity);
+        if !touched && !gone && !still_close.contains_key(&(*player_entity, *enemy_entity)) {
+            run_stats.near_misses += 1;
+        }
+    }
+
+    *close_pairs = still_close;
+}
@@ -1,23 +1,60 @@
 use bevy::prelude::*;
 
+use crate::controls::resources::{Action, ActionState};
+use crate::events::RestartRun;
+
+use super::resources::*;
 use super::SimulationState;
 
 pub fn toggle_simulation(
     mut commmands: Commands,
-    keyboard_input: Res<Input<KeyCode>>,
+    action_state: Res<ActionState>,
     simulation_state: Res<State<SimulationState>>,
 ) {
-    if keyboard_input.just_pressed(KeyCode::Space) {
+    if action_state.just_pressed(Action::Pause) {
         match simulation_state.get() {
             SimulationState::Paused => {
-                commmands.insert_resource(NextState(Some(SimulationState::Running)));
-                println!("Simulatuin Running");
+                commmands.insert_resource(NextState(Some(SimulationState::Countdown)));
             }
-            SimulationState::Running => {
+            SimulationState::Running | SimulationState::Countdown => {
                 commmands.insert_resource(NextState(Some(SimulationState::Paused)));
-                println!("Simulatuin Paused");
             }
         }
-  
     }
+
+    // Back only opens the pause menu; the menu's Resume button takes it from there.
+    if action_state.just_pressed(Action::Back)
+        && *simulation_state.get() != SimulationState::Paused
+    {
+        commmands.insert_resource(NextState(Some(SimulationState::Paused)));
+    }
+}
+
+/// Throws the current run away and starts over without going back through the menu.
+pub fn restart_run(
+    mut commands: Commands,
+    action_state: Res<ActionState>,
+    mut restart_run_event_writer: EventWriter<RestartRun>,
+) {
+    if action_state.just_pressed(Action::Restart) {
+        restart_run_event_writer.send(RestartRun);
+        commands.insert_resource(NextState(Some(SimulationState::Countdown)));
+    }
+}
+
+pub fn start_countdown(mut commands: Commands) {
+    commands.insert_resource(NextState(Some(SimulationState::Countdown)));
+}
+
+/// Leaves the simulation paused between runs, so entering the game always starts a fresh countdown.
+pub fn park_simulation(mut commands: Commands) {
+    commands.insert_resource(NextState(Some(SimulationState::Paused)));
+}
+
+pub fn reset_run_time(mut run_time: ResMut<RunTime>) {
+    run_time.stopwatch.reset();
+}
+
+pub fn tick_run_time(mut run_time: ResMut<RunTime>, time: Res<Time>) {
+    run_time.stopwatch.tick(time.delta());
 }
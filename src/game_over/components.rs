@@ -0,0 +1,25 @@
+use bevy::prelude::*;
+
+
+#[derive(Component)]
+pub struct GameOverScreen;
+
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+pub enum GameOverButton {
+    Retry,
+    MainMenu,
+}
+
+#[derive(Component)]
+pub struct NameEntryScreen;
+
+#[derive(Component)]
+pub struct NameText;
+
+/// A key of the on-screen keyboard.
+#[derive(Component, Clone, Copy)]
+pub enum NameEntryKey {
+    Letter(char),
+    Delete,
+    Confirm,
+}
@@ -0,0 +1,42 @@
+use bevy::prelude::*;
+
+pub mod components;
+pub mod resources;
+mod systems;
+
+use crate::game::score::resources::PendingHighScore;
+use crate::AppState;
+
+use self::{resources::*, systems::*};
+
+pub const MAX_NAME_LENGTH: usize = 12;
+pub const KEYBOARD_LETTERS: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+pub const KEYBOARD_COLUMNS: usize = 10;
+
+pub struct GameOverPlugin;
+
+impl Plugin for GameOverPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(AppState::GameOver),
+            (
+                build_game_over_summary,
+                spawn_name_entry.run_if(resource_exists::<PendingHighScore>()),
+                show_game_over_screen.run_if(not(resource_exists::<PendingHighScore>())),
+            )
+                .chain(),
+        )
+        .add_systems(
+            Update,
+            (type_name, press_name_entry_keys, update_name_text)
+                .chain()
+                .run_if(in_state(AppState::GameOver))
+                .run_if(resource_exists::<NameEntry>()),
+        )
+        .add_systems(
+            Update,
+            interact_with_game_over_buttons.run_if(in_state(AppState::GameOver)),
+        )
+        .add_systems(OnExit(AppState::GameOver), clear_game_over_resources);
+    }
+}
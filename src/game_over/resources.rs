@@ -0,0 +1,28 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::events::PlayerResult;
+use crate::game::stats::resources::RunStats;
+
+
+/// The name being typed for a new high score.
+#[derive(Resource, Debug, Default)]
+pub struct NameEntry {
+    pub name: String,
+}
+
+/// What the game over screen reports about the run that just ended.
+#[derive(Resource, Debug)]
+pub struct GameOverSummary {
+    pub score: u32,
+    pub time_survived: Duration,
+    pub enemies_left: usize,
+    /// Zero-based place in the high-score table, if the score made it in.
+    pub rank: Option<usize>,
+    pub best_score: Option<u32>,
+    /// Per-player breakdown, only shown when more than one played.
+    pub players: Vec<PlayerResult>,
+    pub difficulty_level: u32,
+    pub stats: RunStats,
+}
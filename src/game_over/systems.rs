@@ -0,0 +1,381 @@
+use bevy::prelude::*;
+
+use crate::controls::resources::{Action, ActionState};
+use crate::events::{ButtonActivated, GameOver, HighScoreEntered};
+use crate::game::enemy::components::{Enemy, EnemyKind};
+use crate::game::resources::{format_run_time, RunTime};
+use crate::game::score::resources::*;
+use crate::game::star::components::StarKind;
+use crate::game::stats::resources::RunStats;
+use crate::state_scoped::StateScoped;
+use crate::ui::components::*;
+use crate::ui::styles::*;
+use crate::ui::SCREEN_LAYER;
+use crate::AppState;
+
+use super::components::*;
+use super::resources::*;
+use super::{KEYBOARD_COLUMNS, KEYBOARD_LETTERS, MAX_NAME_LENGTH};
+
+
+pub fn build_game_over_summary(
+    mut commands: Commands,
+    score: Res<Score>,
+    run_time: Res<RunTime>,
+    high_scores: Res<HighScores>,
+    pending_high_score: Option<Res<PendingHighScore>>,
+    enemy_query: Query<&Enemy>,
+    mut game_over_event_reader: EventReader<GameOver>,
+) {
+    let (players, difficulty_level, stats) = game_over_event_reader
+        .read()
+        .last()
+        .map(|game_over| (game_over.players.clone(), game_over.difficulty_level, game_over.stats.clone()))
+        .unwrap_or_else(|| (Vec::new(), 1, RunStats::default()));
+
+    commands.insert_resource(GameOverSummary {
+        score: score.value,
+        time_survived: run_time.stopwatch.elapsed(),
+        enemies_left: enemy_query.iter().filter(|enemy| enemy.kind != EnemyKind::Boss).count(),
+        rank: pending_high_score.map(|pending_high_score| pending_high_score.rank),
+        best_score: high_scores.scores.first().map(|(_, best)| *best),
+        players,
+        difficulty_level,
+        stats,
+    });
+}
+
+pub fn show_game_over_screen(mut commands: Commands, summary: Res<GameOverSummary>) {
+    spawn_game_over_screen(&mut commands, &summary);
+}
+
+fn spawn_game_over_screen(commands: &mut Commands, summary: &GameOverSummary) {
+    let rank_line = match (summary.rank, summary.best_score) {
+        (Some(rank), _) => format!("High score rank: #{}", rank + 1),
+        (None, Some(best_score)) => format!("Best score to beat: {}", best_score),
+        (None, None) => "No high scores yet".to_string(),
+    };
+    let buttons = [
+        (GameOverButton::Retry, "Retry"),
+        (GameOverButton::MainMenu, "Main Menu"),
+    ];
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: screen_style(),
+                background_color: OVERLAY_COLOR.into(),
+                ..default()
+            },
+            FocusGroup {
+                columns: 1,
+                layer: SCREEN_LAYER,
+            },
+            GameOverScreen,
+            StateScoped(AppState::GameOver),
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section("Game Over", title_text_style()));
+            parent.spawn(TextBundle::from_section(
+                format!("Final score: {}", summary.score),
+                body_text_style(),
+            ));
+            parent.spawn(TextBundle::from_section(
+                format!("Time survived: {}", format_run_time(summary.time_survived)),
+                body_text_style(),
+            ));
+            parent.spawn(TextBundle::from_section(
+                format!("Enemies on the field: {}", summary.enemies_left),
+                body_text_style(),
+            ));
+            parent.spawn(TextBundle::from_section(
+                format!("Difficulty reached: level {}", summary.difficulty_level),
+                body_text_style(),
+            ));
+            if summary.players.len() > 1 {
+                for result in summary.players.iter() {
+                    parent.spawn(TextBundle::from_section(
+                        format!(
+                            "P{}: {} points, out at {}",
+                            result.player + 1,
+                            result.score,
+                            format_run_time(result.time_survived)
+                        ),
+                        body_text_style(),
+                    ));
+                }
+            }
+            for line in stats_lines(&summary.stats) {
+                parent.spawn(TextBundle::from_section(line, detail_text_style()));
+            }
+            parent.spawn(TextBundle::from_section(rank_line, body_text_style()));
+
+            for (index, (button, label)) in buttons.into_iter().enumerate() {
+                let mut button_entity = parent.spawn((
+                    ButtonBundle {
+                        style: button_style(),
+                        background_color: NORMAL_BUTTON_COLOR.into(),
+                        ..default()
+                    },
+                    Focusable { index },
+                    button,
+                ));
+                button_entity.with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(label, button_text_style()));
+                });
+
+                if button == GameOverButton::MainMenu {
+                    button_entity.insert(CancelButton);
+                }
+            }
+        });
+}
+
+/// The run breakdown, a few facts to a line to keep the screen short.
+fn stats_lines(stats: &RunStats) -> Vec<String> {
+    let special_stars: Vec<String> = StarKind::ALL
+        .into_iter()
+        .filter(|kind| *kind != StarKind::Plain && stats.stars_of_kind(*kind) > 0)
+        .map(|kind| format!("{} {}", stats.stars_of_kind(kind), kind.label()))
+        .collect();
+    let stars_line = if special_stars.is_empty() {
+        format!("Stars collected: {}", stats.total_stars())
+    } else {
+        format!("Stars collected: {} ({})", stats.total_stars(), special_stars.join(", "))
+    };
+
+    let mut lines = vec![
+        stars_line,
+        format!(
+            "Enemies spawned: {}  Wall bounces: {}  Near misses: {}",
+            stats.enemies_spawned, stats.wall_bounces, stats.near_misses
+        ),
+        format!("Distance travelled: {:.0} px", stats.distance_travelled),
+    ];
+
+    if let Some(cause) = &stats.cause_of_death {
+        let culprit = match cause.enemy {
+            EnemyKind::Boss => "the boss".to_string(),
+            kind => format!("a {}", kind.label()),
+        };
+        lines.push(format!(
+            "P{} downed by {} at ({:.0}, {:.0})",
+            cause.player + 1,
+            culprit,
+            cause.position.x,
+            cause.position.y
+        ));
+    }
+
+    lines
+}
+
+pub fn interact_with_game_over_buttons(
+    mut commands: Commands,
+    mut button_activated_event_reader: EventReader<ButtonActivated>,
+    button_query: Query<&GameOverButton>,
+) {
+    for event in button_activated_event_reader.read() {
+        match button_query.get(event.entity) {
+            Ok(GameOverButton::Retry) => {
+                commands.insert_resource(NextState(Some(AppState::Game)));
+            }
+            Ok(GameOverButton::MainMenu) => {
+                commands.insert_resource(NextState(Some(AppState::MainMenu)));
+            }
+            Err(_) => {}
+        }
+    }
+}
+
+pub fn spawn_name_entry(
+    mut commands: Commands,
+    pending_high_score: Res<PendingHighScore>,
+    high_scores: Res<HighScores>,
+) {
+    commands.insert_resource(NameEntry {
+        name: high_scores.default_name().to_string(),
+    });
+
+    let keys: Vec<NameEntryKey> = KEYBOARD_LETTERS
+        .chars()
+        .map(NameEntryKey::Letter)
+        .chain([NameEntryKey::Delete, NameEntryKey::Confirm])
+        .collect();
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: screen_style(),
+                background_color: OVERLAY_COLOR.into(),
+                ..default()
+            },
+            NameEntryScreen,
+            StateScoped(AppState::GameOver),
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section("New high score!", title_text_style()));
+            parent.spawn(TextBundle::from_section(
+                format!(
+                    "You placed #{} with {} points",
+                    pending_high_score.rank + 1,
+                    pending_high_score.score
+                ),
+                body_text_style(),
+            ));
+            parent.spawn((
+                TextBundle::from_section("", title_text_style()),
+                NameText,
+            ));
+
+            parent
+                .spawn((
+                    NodeBundle {
+                        style: Style {
+                            flex_direction: FlexDirection::Column,
+                            row_gap: Val::Px(6.0),
+                            ..default()
+                        },
+                        ..default()
+                    },
+                    FocusGroup {
+                        columns: KEYBOARD_COLUMNS,
+                        layer: SCREEN_LAYER,
+                    },
+                ))
+                .with_children(|parent| {
+                    for (row, row_keys) in keys.chunks(KEYBOARD_COLUMNS).enumerate() {
+                        parent
+                            .spawn(NodeBundle {
+                                style: Style {
+                                    column_gap: Val::Px(6.0),
+                                    ..default()
+                                },
+                                ..default()
+                            })
+                            .with_children(|parent| {
+                                for (column, key) in row_keys.iter().enumerate() {
+                                    spawn_key(parent, *key, row * KEYBOARD_COLUMNS + column);
+                                }
+                            });
+                    }
+                });
+
+            parent.spawn(TextBundle::from_section(
+                "Type or pick letters, Enter to confirm",
+                body_text_style(),
+            ));
+        });
+}
+
+fn spawn_key(parent: &mut ChildBuilder, key: NameEntryKey, index: usize) {
+    let (label, style) = match key {
+        NameEntryKey::Letter(letter) => (letter.to_string(), small_button_style()),
+        NameEntryKey::Delete => ("DEL".to_string(), Style {
+            width: Val::Px(102.0),
+            ..small_button_style()
+        }),
+        NameEntryKey::Confirm => ("OK".to_string(), Style {
+            width: Val::Px(102.0),
+            ..small_button_style()
+        }),
+    };
+
+    let mut key_entity = parent.spawn((
+        ButtonBundle {
+            style,
+            background_color: NORMAL_BUTTON_COLOR.into(),
+            ..default()
+        },
+        Focusable { index },
+        key,
+    ));
+    key_entity.with_children(|parent| {
+        parent.spawn(TextBundle::from_section(label, button_text_style()));
+    });
+
+    // Keyboard players type their name, so Enter should confirm straight away.
+    if matches!(key, NameEntryKey::Confirm) {
+        key_entity.insert(Focused);
+    }
+}
+
+pub fn clear_game_over_resources(mut commands: Commands) {
+    commands.remove_resource::<NameEntry>();
+    commands.remove_resource::<GameOverSummary>();
+}
+
+pub fn type_name(
+    mut received_character_event_reader: EventReader<ReceivedCharacter>,
+    action_state: Res<ActionState>,
+    mut name_entry: ResMut<NameEntry>,
+) {
+    for event in received_character_event_reader.read() {
+        if (event.char.is_ascii_alphanumeric() || event.char == ' ')
+            && name_entry.name.chars().count() < MAX_NAME_LENGTH
+        {
+            name_entry.name.push(event.char);
+        }
+    }
+
+    if action_state.just_pressed(Action::Erase) {
+        name_entry.name.pop();
+    }
+}
+
+pub fn press_name_entry_keys(
+    mut commands: Commands,
+    mut button_activated_event_reader: EventReader<ButtonActivated>,
+    mut high_score_entered_event_writer: EventWriter<HighScoreEntered>,
+    action_state: Res<ActionState>,
+    key_query: Query<&NameEntryKey>,
+    screen_query: Query<Entity, With<NameEntryScreen>>,
+    mut name_entry: ResMut<NameEntry>,
+    pending_high_score: Res<PendingHighScore>,
+    summary: Res<GameOverSummary>,
+) {
+    let mut confirmed = action_state.just_pressed(Action::Submit);
+
+    for event in button_activated_event_reader.read() {
+        match key_query.get(event.entity) {
+            Ok(NameEntryKey::Letter(letter)) => {
+                if name_entry.name.chars().count() < MAX_NAME_LENGTH {
+                    name_entry.name.push(*letter);
+                }
+            }
+            Ok(NameEntryKey::Delete) => {
+                name_entry.name.pop();
+            }
+            Ok(NameEntryKey::Confirm) => confirmed = true,
+            Err(_) => {}
+        }
+    }
+
+    if confirmed {
+        let name = name_entry.name.trim();
+        high_score_entered_event_writer.send(HighScoreEntered {
+            name: if name.is_empty() { DEFAULT_PLAYER_NAME } else { name }.to_string(),
+            score: pending_high_score.score,
+        });
+
+        for screen_entity in screen_query.iter() {
+            commands.entity(screen_entity).despawn_recursive();
+        }
+        commands.remove_resource::<NameEntry>();
+        spawn_game_over_screen(&mut commands, &summary);
+    }
+}
+
+pub fn update_name_text(
+    name_entry: Res<NameEntry>,
+    mut text_query: Query<&mut Text, With<NameText>>,
+) {
+    if !name_entry.is_changed() {
+        return;
+    }
+
+    for mut text in text_query.iter_mut() {
+        let cursor = if name_entry.name.chars().count() < MAX_NAME_LENGTH { "_" } else { "" };
+        text.sections[0].value = format!("{}{}", name_entry.name, cursor);
+    }
+}
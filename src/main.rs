@@ -1,11 +1,27 @@
+// Bevy systems take their data as parameters and queries, which trips these lints.
+#![allow(clippy::too_many_arguments, clippy::type_complexity)]
+// `is_multiple_of` is newer than the toolchains Bevy 0.12 supports, so `% n == 0` stays.
+#![allow(clippy::manual_is_multiple_of)]
+
 use bevy::prelude::*;
+use controls::ControlsPlugin;
 use game::GamePlugin;
+use game_over::GameOverPlugin;
 use main_menu::MainMenuPlugin;
+use settings::SettingsPlugin;
+use state_scoped::StateScopedPlugin;
+use ui::UiPlugin;
 
 pub mod events;
+pub mod persistence;
 pub mod systems;
+mod controls;
 mod game;
+mod game_over;
 mod main_menu;
+mod settings;
+mod state_scoped;
+mod ui;
 
 use crate::systems::*;
 
@@ -13,13 +29,23 @@ fn main() {
     App::new()
         .add_plugins(DefaultPlugins)
         .add_state::<AppState>()
+        // The arena stays on screen, frozen, behind the game over screen.
+        .add_plugins(StateScopedPlugin::<AppState>::default().carry_over(AppState::Game, AppState::GameOver))
+        .add_plugins(UiPlugin)
+        .add_plugins(SettingsPlugin)
+        .add_plugins(ControlsPlugin)
         .add_plugins(MainMenuPlugin)
         .add_plugins(GamePlugin)
+        .add_plugins(GameOverPlugin)
         .add_systems(Startup, spawn_camera)
-        .add_systems(Update, transition_to_game_state)
-        .add_systems(Update, transition_to_main_menu_state)
-        .add_systems(Update, exit_game)
-        .add_systems(Update, handle_game_over)
+        // G and M are letters of the name entry keyboard, so the hotkeys sit out that screen.
+        .add_systems(
+            Update,
+            (transition_to_game_state, transition_to_main_menu_state)
+                .run_if(debug_shortcuts_enabled)
+                .run_if(not(in_state(AppState::GameOver))),
+        )
+        .add_systems(Update, handle_game_over.run_if(in_state(AppState::Game)))
         .run();
 }
 
@@ -0,0 +1,19 @@
+use bevy::prelude::*;
+
+
+#[derive(Component)]
+pub struct MainMenu;
+
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+pub enum MainMenuButton {
+    Play,
+    HighScores,
+    Settings,
+    Quit,
+}
+
+#[derive(Component)]
+pub struct HighScoresPanel;
+
+#[derive(Component)]
+pub struct CloseHighScoresButton;
@@ -1,14 +1,22 @@
 use bevy::prelude::*;
 
+pub mod components;
+mod systems;
+
+use crate::AppState;
+
+use self::systems::*;
 
 pub struct MainMenuPlugin;
 
 impl Plugin for MainMenuPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, main_menu);
+        app.add_systems(OnEnter(AppState::MainMenu), spawn_main_menu)
+            .add_systems(
+                Update,
+                (interact_with_main_menu_buttons, interact_with_high_scores_panel)
+                    .run_if(in_state(AppState::MainMenu)),
+            )
+            .add_systems(OnExit(AppState::MainMenu), close_settings_menu);
     }
 }
-
-pub fn main_menu() {
-    println!("You are on the main menu.")
-}
\ No newline at end of file
@@ -0,0 +1,148 @@
+use bevy::app::AppExit;
+use bevy::prelude::*;
+use bevy::ui::FocusPolicy;
+
+use crate::events::ButtonActivated;
+use crate::game::score::resources::HighScores;
+use crate::settings::SettingsMenuState;
+use crate::state_scoped::StateScoped;
+use crate::ui::components::*;
+use crate::ui::styles::*;
+use crate::ui::{PANEL_LAYER, SCREEN_LAYER};
+use crate::AppState;
+
+use super::components::*;
+
+
+pub fn spawn_main_menu(mut commands: Commands) {
+    let buttons = [
+        (MainMenuButton::Play, "Play"),
+        (MainMenuButton::HighScores, "High Scores"),
+        (MainMenuButton::Settings, "Settings"),
+        (MainMenuButton::Quit, "Quit"),
+    ];
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: screen_style(),
+                ..default()
+            },
+            FocusGroup {
+                columns: 1,
+                layer: SCREEN_LAYER,
+            },
+            MainMenu,
+            StateScoped(AppState::MainMenu),
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section("Bevy Ball Game", title_text_style()));
+
+            for (index, (button, label)) in buttons.into_iter().enumerate() {
+                let mut button_entity = parent.spawn((
+                    ButtonBundle {
+                        style: button_style(),
+                        background_color: NORMAL_BUTTON_COLOR.into(),
+                        ..default()
+                    },
+                    Focusable { index },
+                    button,
+                ));
+                button_entity.with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(label, button_text_style()));
+                });
+
+                if button == MainMenuButton::Quit {
+                    button_entity.insert(CancelButton);
+                }
+            }
+        });
+}
+
+pub fn close_settings_menu(mut commands: Commands) {
+    commands.insert_resource(NextState(Some(SettingsMenuState::Closed)));
+}
+
+pub fn interact_with_main_menu_buttons(
+    mut commands: Commands,
+    mut button_activated_event_reader: EventReader<ButtonActivated>,
+    mut app_exit_event_writer: EventWriter<AppExit>,
+    button_query: Query<&MainMenuButton>,
+    high_scores: Res<HighScores>,
+) {
+    for event in button_activated_event_reader.read() {
+        match button_query.get(event.entity) {
+            Ok(MainMenuButton::Play) => {
+                commands.insert_resource(NextState(Some(AppState::Game)));
+            }
+            Ok(MainMenuButton::HighScores) => spawn_high_scores_panel(&mut commands, &high_scores),
+            Ok(MainMenuButton::Settings) => {
+                commands.insert_resource(NextState(Some(SettingsMenuState::Open)));
+            }
+            Ok(MainMenuButton::Quit) => app_exit_event_writer.send(AppExit),
+            Err(_) => {}
+        }
+    }
+}
+
+fn spawn_high_scores_panel(commands: &mut Commands, high_scores: &HighScores) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: screen_style(),
+                background_color: OVERLAY_COLOR.into(),
+                focus_policy: FocusPolicy::Block,
+                z_index: ZIndex::Global(PANEL_LAYER as i32),
+                ..default()
+            },
+            FocusGroup {
+                columns: 1,
+                layer: PANEL_LAYER,
+            },
+            HighScoresPanel,
+            StateScoped(AppState::MainMenu),
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section("High Scores", title_text_style()));
+
+            if high_scores.scores.is_empty() {
+                parent.spawn(TextBundle::from_section("No scores yet", body_text_style()));
+            }
+            for (rank, (name, score)) in high_scores.scores.iter().enumerate() {
+                parent.spawn(TextBundle::from_section(
+                    format!("{:>2}. {:<12} {:>6}", rank + 1, name, score),
+                    body_text_style(),
+                ));
+            }
+
+            parent
+                .spawn((
+                    ButtonBundle {
+                        style: button_style(),
+                        background_color: NORMAL_BUTTON_COLOR.into(),
+                        ..default()
+                    },
+                    Focusable { index: 0 },
+                    CancelButton,
+                    CloseHighScoresButton,
+                ))
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section("Back", button_text_style()));
+                });
+        });
+}
+
+pub fn interact_with_high_scores_panel(
+    mut commands: Commands,
+    mut button_activated_event_reader: EventReader<ButtonActivated>,
+    close_button_query: Query<(), With<CloseHighScoresButton>>,
+    panel_query: Query<Entity, With<HighScoresPanel>>,
+) {
+    for event in button_activated_event_reader.read() {
+        if close_button_query.contains(event.entity) {
+            for panel_entity in panel_query.iter() {
+                commands.entity(panel_entity).despawn_recursive();
+            }
+        }
+    }
+}
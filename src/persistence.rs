@@ -0,0 +1,44 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const APP_DIR_NAME: &str = "bevy_fp";
+
+/// Directory the game keeps its save files in, e.g. `~/.local/share/bevy_fp` on Linux.
+/// Falls back to the working directory when the platform has no data directory.
+pub fn data_dir() -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(APP_DIR_NAME)
+}
+
+/// Writes `contents` to a temporary file next to `path` and renames it over `path`,
+/// so a crash mid-write never leaves a half-written save behind.
+pub fn write_atomically(path: &Path, contents: &str) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let tmp_path = path.with_extension("tmp");
+    {
+        let mut file = fs::File::create(&tmp_path)?;
+        file.write_all(contents.as_bytes())?;
+        file.sync_all()?;
+    }
+
+    fs::rename(&tmp_path, path)
+}
+
+/// Moves a save file that could not be read out of the way, keeping it for inspection.
+/// Returns the path of the backup.
+pub fn back_up(path: &Path) -> io::Result<PathBuf> {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis())
+        .unwrap_or_default();
+    let backup_path = path.with_extension(format!("{timestamp}.bak"));
+
+    fs::rename(path, &backup_path)?;
+    Ok(backup_path)
+}
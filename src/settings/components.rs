@@ -0,0 +1,25 @@
+use bevy::prelude::*;
+
+
+#[derive(Component)]
+pub struct SettingsMenu;
+
+/// A row of the settings menu. Its text child carries the same value as a `SettingLabel`.
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+pub enum SettingsButton {
+    MasterVolume,
+    CountdownLength,
+    Players,
+    StartingLives,
+    EnemyCollisions,
+    Steering,
+    Movement,
+    DebugShortcuts,
+    HudAnchor,
+    HudScale,
+    Controls,
+    Back,
+}
+
+#[derive(Component)]
+pub struct SettingLabel(pub SettingsButton);
@@ -0,0 +1,49 @@
+use bevy::prelude::*;
+
+pub mod components;
+pub mod resources;
+mod storage;
+mod systems;
+
+use crate::state_scoped::StateScopedPlugin;
+
+use self::{resources::*, systems::*};
+
+pub const SETTINGS_FILE: &str = "settings.ron";
+pub const VOLUME_STEP: f32 = 0.1;
+pub const MAX_COUNTDOWN_SECONDS: u32 = 5;
+pub const MAX_STARTING_LIVES: u32 = 5;
+pub const HUD_SCALE_STEP: f32 = 0.25;
+pub const MIN_HUD_SCALE: f32 = 0.5;
+pub const MAX_HUD_SCALE: f32 = 2.0;
+
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_state::<SettingsMenuState>()
+            .add_plugins(StateScopedPlugin::<SettingsMenuState>::default())
+            .init_resource::<Settings>()
+            .add_systems(Startup, load_settings_on_startup)
+            .add_systems(OnEnter(SettingsMenuState::Open), spawn_settings_menu)
+            .add_systems(
+                Update,
+                (interact_with_settings_buttons, update_settings_labels)
+                    .chain()
+                    .run_if(in_state(SettingsMenuState::Open)),
+            )
+            .add_systems(Update, apply_volume.run_if(resource_changed::<Settings>()))
+            .add_systems(OnExit(SettingsMenuState::Open), save_settings);
+    }
+}
+
+
+/// Whether the settings overlay is on screen. It can open on top of any menu.
+#[derive(States, Debug, Clone, Copy, Hash, PartialEq, Eq, Default)]
+pub enum SettingsMenuState {
+    #[default]
+    Closed,
+    Open,
+    /// The key bindings screen, reached from the settings overlay.
+    Controls,
+}
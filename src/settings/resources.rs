@@ -0,0 +1,112 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+
+/// Player preferences, saved next to the high scores.
+/// Missing fields fall back to their defaults, so older files keep loading as settings are added.
+#[derive(Resource, Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub master_volume: f32,
+    /// Length of the "3-2-1-Go" countdown before a run starts or resumes. Zero skips it.
+    pub countdown_seconds: u32,
+    /// Enables the G and M hotkeys that jump straight to the game or the main menu.
+    pub debug_shortcuts: bool,
+    /// What steers the first player.
+    pub control_scheme: ControlScheme,
+    /// How the player responds to input; see `MovementModel`.
+    pub movement_preset: MovementPreset,
+    /// Local players, each on their own device.
+    pub player_count: usize,
+    /// Lives each player starts a run with.
+    pub starting_lives: u32,
+    /// Whether enemies bounce off each other or pass straight through.
+    pub enemy_collisions: bool,
+    /// Screen corner the in-game HUD sits in.
+    pub hud_anchor: HudAnchor,
+    /// Multiplier for the HUD's text size.
+    pub hud_scale: f32,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            master_volume: 1.0,
+            countdown_seconds: 3,
+            debug_shortcuts: cfg!(debug_assertions),
+            control_scheme: ControlScheme::Buttons,
+            movement_preset: MovementPreset::Instant,
+            player_count: 1,
+            starting_lives: 3,
+            enemy_collisions: true,
+            hud_anchor: HudAnchor::TopLeft,
+            hud_scale: 1.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ControlScheme {
+    /// Keys, d-pad and stick through the action map.
+    #[default]
+    Buttons,
+    /// Heads for the mouse cursor or the finger on the screen.
+    PointerFollow,
+    /// Pressing anywhere drops a joystick there; dragging away from it steers.
+    VirtualJoystick,
+}
+
+impl ControlScheme {
+    pub fn next(self) -> Self {
+        match self {
+            ControlScheme::Buttons => ControlScheme::PointerFollow,
+            ControlScheme::PointerFollow => ControlScheme::VirtualJoystick,
+            ControlScheme::VirtualJoystick => ControlScheme::Buttons,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ControlScheme::Buttons => "Buttons",
+            ControlScheme::PointerFollow => "Follow Pointer",
+            ControlScheme::VirtualJoystick => "Virtual Stick",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum MovementPreset {
+    #[default]
+    Instant,
+    Inertia,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum HudAnchor {
+    #[default]
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl HudAnchor {
+    /// The next corner clockwise.
+    pub fn next(self) -> Self {
+        match self {
+            HudAnchor::TopLeft => HudAnchor::TopRight,
+            HudAnchor::TopRight => HudAnchor::BottomRight,
+            HudAnchor::BottomRight => HudAnchor::BottomLeft,
+            HudAnchor::BottomLeft => HudAnchor::TopLeft,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            HudAnchor::TopLeft => "Top Left",
+            HudAnchor::TopRight => "Top Right",
+            HudAnchor::BottomLeft => "Bottom Left",
+            HudAnchor::BottomRight => "Bottom Right",
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use bevy::prelude::*;
+use ron::ser::PrettyConfig;
+
+use crate::persistence::{back_up, data_dir, write_atomically};
+
+use super::resources::Settings;
+use super::SETTINGS_FILE;
+
+pub fn settings_path() -> PathBuf {
+    data_dir().join(SETTINGS_FILE)
+}
+
+/// Reads the settings at `path`, falling back to the defaults when there are none yet.
+/// An unreadable file is backed up rather than overwritten on the next save.
+pub fn load_settings(path: &Path) -> Settings {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Settings::default(),
+        Err(error) => {
+            warn!("Could not read settings from {}: {}", path.display(), error);
+            return Settings::default();
+        }
+    };
+
+    match ron::from_str::<Settings>(&contents) {
+        Ok(settings) => settings,
+        Err(error) => {
+            match back_up(path) {
+                Ok(backup_path) => warn!(
+                    "Settings in {} are unreadable ({}), moved to {}",
+                    path.display(),
+                    error,
+                    backup_path.display()
+                ),
+                Err(backup_error) => warn!(
+                    "Settings in {} are unreadable ({}) and could not be backed up: {}",
+                    path.display(),
+                    error,
+                    backup_error
+                ),
+            }
+            Settings::default()
+        }
+    }
+}
+
+pub fn write_settings(settings: &Settings, path: &Path) -> io::Result<()> {
+    let contents = ron::ser::to_string_pretty(settings, PrettyConfig::default())
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+    write_atomically(path, &contents)
+}
@@ -0,0 +1,204 @@
+use bevy::prelude::*;
+
+use crate::events::ButtonActivated;
+use crate::game::player::MAX_PLAYERS;
+use crate::state_scoped::StateScoped;
+use crate::ui::components::*;
+use crate::ui::styles::*;
+use crate::ui::SETTINGS_LAYER;
+
+use super::components::*;
+use super::resources::*;
+use super::storage::*;
+use super::{
+    SettingsMenuState, HUD_SCALE_STEP, MAX_COUNTDOWN_SECONDS, MAX_HUD_SCALE, MAX_STARTING_LIVES, MIN_HUD_SCALE,
+    VOLUME_STEP,
+};
+
+
+pub fn load_settings_on_startup(mut commands: Commands) {
+    commands.insert_resource(load_settings(&settings_path()));
+}
+
+pub fn save_settings(settings: Res<Settings>) {
+    let path = settings_path();
+    if let Err(error) = write_settings(&settings, &path) {
+        error!("Could not save settings to {}: {}", path.display(), error);
+    }
+}
+
+pub fn apply_volume(mut commands: Commands, settings: Res<Settings>) {
+    commands.insert_resource(GlobalVolume::new(settings.master_volume));
+}
+
+pub fn spawn_settings_menu(mut commands: Commands, settings: Res<Settings>) {
+    let buttons = [
+        SettingsButton::MasterVolume,
+        SettingsButton::CountdownLength,
+        SettingsButton::Players,
+        SettingsButton::StartingLives,
+        SettingsButton::EnemyCollisions,
+        SettingsButton::Steering,
+        SettingsButton::Movement,
+        SettingsButton::DebugShortcuts,
+        SettingsButton::HudAnchor,
+        SettingsButton::HudScale,
+        SettingsButton::Controls,
+        SettingsButton::Back,
+    ];
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    row_gap: Val::Px(6.0),
+                    ..screen_style()
+                },
+                background_color: OVERLAY_COLOR.into(),
+                // Keep clicks away from the menu underneath.
+                focus_policy: bevy::ui::FocusPolicy::Block,
+                z_index: ZIndex::Global(SETTINGS_LAYER as i32),
+                ..default()
+            },
+            FocusGroup {
+                columns: 1,
+                layer: SETTINGS_LAYER,
+            },
+            SettingsMenu,
+            StateScoped(SettingsMenuState::Open),
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section("Settings", title_text_style()));
+
+            for (index, button) in buttons.into_iter().enumerate() {
+                let mut button_entity = parent.spawn((
+                    ButtonBundle {
+                        style: list_button_style(),
+                        background_color: NORMAL_BUTTON_COLOR.into(),
+                        ..default()
+                    },
+                    Focusable { index },
+                    button,
+                ));
+                button_entity.with_children(|parent| {
+                    parent.spawn((
+                        TextBundle::from_section(setting_label(button, &settings), list_button_text_style()),
+                        SettingLabel(button),
+                    ));
+                });
+
+                if button == SettingsButton::Back {
+                    button_entity.insert(CancelButton);
+                }
+            }
+        });
+}
+
+pub fn interact_with_settings_buttons(
+    mut commands: Commands,
+    mut button_activated_event_reader: EventReader<ButtonActivated>,
+    button_query: Query<&SettingsButton>,
+    mut settings: ResMut<Settings>,
+    gamepads: Res<Gamepads>,
+) {
+    for event in button_activated_event_reader.read() {
+        match button_query.get(event.entity) {
+            Ok(SettingsButton::MasterVolume) => {
+                // Step up to full volume, then wrap back around to muted.
+                let steps = (settings.master_volume / VOLUME_STEP).round() as u32 + 1;
+                let max_steps = (1.0 / VOLUME_STEP).round() as u32;
+                settings.master_volume = (steps % (max_steps + 1)) as f32 * VOLUME_STEP;
+            }
+            Ok(SettingsButton::CountdownLength) => {
+                settings.countdown_seconds = (settings.countdown_seconds + 1) % (MAX_COUNTDOWN_SECONDS + 1);
+            }
+            Ok(SettingsButton::Players) => {
+                // Everyone past the first needs a gamepad, so only offer as many players as there are pads for.
+                let max_players = (gamepads.iter().count() + 1).min(MAX_PLAYERS);
+                settings.player_count = settings.player_count % max_players + 1;
+            }
+            Ok(SettingsButton::StartingLives) => {
+                settings.starting_lives = settings.starting_lives % MAX_STARTING_LIVES + 1;
+            }
+            Ok(SettingsButton::EnemyCollisions) => {
+                settings.enemy_collisions = !settings.enemy_collisions;
+            }
+            Ok(SettingsButton::Steering) => {
+                settings.control_scheme = settings.control_scheme.next();
+            }
+            Ok(SettingsButton::Movement) => {
+                settings.movement_preset = match settings.movement_preset {
+                    MovementPreset::Instant => MovementPreset::Inertia,
+                    MovementPreset::Inertia => MovementPreset::Instant,
+                };
+            }
+            Ok(SettingsButton::DebugShortcuts) => {
+                settings.debug_shortcuts = !settings.debug_shortcuts;
+            }
+            Ok(SettingsButton::HudAnchor) => {
+                settings.hud_anchor = settings.hud_anchor.next();
+            }
+            Ok(SettingsButton::HudScale) => {
+                let scale = settings.hud_scale + HUD_SCALE_STEP;
+                settings.hud_scale = if scale > MAX_HUD_SCALE + f32::EPSILON {
+                    MIN_HUD_SCALE
+                } else {
+                    scale
+                };
+            }
+            Ok(SettingsButton::Controls) => {
+                commands.insert_resource(NextState(Some(SettingsMenuState::Controls)));
+            }
+            Ok(SettingsButton::Back) => {
+                commands.insert_resource(NextState(Some(SettingsMenuState::Closed)));
+            }
+            Err(_) => {}
+        }
+    }
+}
+
+pub fn update_settings_labels(
+    settings: Res<Settings>,
+    mut label_query: Query<(&mut Text, &SettingLabel)>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+
+    for (mut text, label) in label_query.iter_mut() {
+        text.sections[0].value = setting_label(label.0, &settings);
+    }
+}
+
+fn setting_label(button: SettingsButton, settings: &Settings) -> String {
+    let on_off = |enabled: bool| if enabled { "On" } else { "Off" };
+
+    match button {
+        SettingsButton::MasterVolume => {
+            format!("Volume: {}%", (settings.master_volume * 100.0).round())
+        }
+        SettingsButton::CountdownLength => match settings.countdown_seconds {
+            0 => "Countdown: Off".to_string(),
+            seconds => format!("Countdown: {}s", seconds),
+        },
+        SettingsButton::Players => format!("Players: {}", settings.player_count),
+        SettingsButton::StartingLives => format!("Lives: {}", settings.starting_lives),
+        SettingsButton::EnemyCollisions => {
+            format!("Enemy Bounces: {}", on_off(settings.enemy_collisions))
+        }
+        SettingsButton::Steering => format!("Steering: {}", settings.control_scheme.label()),
+        SettingsButton::Movement => match settings.movement_preset {
+            MovementPreset::Instant => "Movement: Instant".to_string(),
+            MovementPreset::Inertia => "Movement: Inertia".to_string(),
+        },
+        SettingsButton::DebugShortcuts => {
+            format!("Debug Keys: {}", on_off(settings.debug_shortcuts))
+        }
+        SettingsButton::HudAnchor => format!("HUD: {}", settings.hud_anchor.label()),
+        SettingsButton::HudScale => {
+            format!("HUD Size: {}%", (settings.hud_scale * 100.0).round())
+        }
+        SettingsButton::Controls => "Controls".to_string(),
+        SettingsButton::Back => "Back".to_string(),
+    }
+}
@@ -0,0 +1,93 @@
+use std::marker::PhantomData;
+
+use bevy::ecs::schedule::apply_state_transition;
+use bevy::prelude::*;
+
+
+/// Despawns the entity and its children when `State<S>` leaves the given state.
+/// An entity can carry one of these per state type, e.g. both an `AppState` and a
+/// `SimulationState` scope; whichever ends first cleans it up.
+#[derive(Component, Debug)]
+pub struct StateScoped<S: States>(pub S);
+
+/// Transitions that keep `StateScoped` entities alive, handing them over to the new state.
+#[derive(Resource)]
+struct CarryOvers<S: States>(Vec<(S, S)>);
+
+/// Cleans up `StateScoped<S>` entities on every transition of `S`.
+pub struct StateScopedPlugin<S: States> {
+    carry_overs: Vec<(S, S)>,
+    marker: PhantomData<S>,
+}
+
+impl<S: States> Default for StateScopedPlugin<S> {
+    fn default() -> Self {
+        Self {
+            carry_overs: Vec::new(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<S: States> StateScopedPlugin<S> {
+    /// Entities scoped to `from` survive a transition straight into `to` and are scoped
+    /// to `to` from then on, e.g. to keep the arena on screen behind the game over screen.
+    pub fn carry_over(mut self, from: S, to: S) -> Self {
+        self.carry_overs.push((from, to));
+        self
+    }
+}
+
+impl<S: States> Plugin for StateScopedPlugin<S> {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(CarryOvers(self.carry_overs.clone()))
+            .add_systems(
+                StateTransition,
+                despawn_state_scoped::<S>.after(apply_state_transition::<S>),
+            );
+    }
+}
+
+fn despawn_state_scoped<S: States>(
+    mut commands: Commands,
+    mut previous_state: Local<Option<S>>,
+    state: Res<State<S>>,
+    carry_overs: Res<CarryOvers<S>>,
+    mut scoped_query: Query<(Entity, &mut StateScoped<S>)>,
+) {
+    let entered = state.get().clone();
+    let Some(exited) = previous_state.replace(entered.clone()) else {
+        return;
+    };
+    if exited == entered {
+        return;
+    }
+
+    let carried_over = carry_overs.0.contains(&(exited.clone(), entered.clone()));
+
+    for (entity, mut scope) in scoped_query.iter_mut() {
+        if scope.0 != exited {
+            continue;
+        }
+
+        if carried_over {
+            scope.0 = entered.clone();
+        } else {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+/// Builds a system that despawns everything scoped to `state` without leaving it,
+/// e.g. to throw away the current run when restarting.
+pub fn despawn_scoped_to<S: States>(
+    state: S,
+) -> impl FnMut(Commands, Query<(Entity, &StateScoped<S>)>) {
+    move |mut commands: Commands, scoped_query: Query<(Entity, &StateScoped<S>)>| {
+        for (entity, scope) in scoped_query.iter() {
+            if scope.0 == state {
+                commands.entity(entity).despawn_recursive();
+            }
+        }
+    }
+}
@@ -1,7 +1,8 @@
 use bevy::prelude::*;
-use bevy::app::AppExit;
 use bevy::window::PrimaryWindow;
 
+use crate::controls::resources::{Action, ActionState};
+use crate::settings::resources::Settings;
 use crate::{events::*, AppState};
 
 pub fn spawn_camera(mut commands: Commands, window_query: Query<&Window, With<PrimaryWindow>>) {
@@ -13,47 +14,39 @@ pub fn spawn_camera(mut commands: Commands, window_query: Query<&Window, With<Pr
     });
 }
 
+/// Run condition for the G/M hotkeys, which skip the menus while testing.
+pub fn debug_shortcuts_enabled(settings: Res<Settings>) -> bool {
+    settings.debug_shortcuts
+}
+
 pub fn transition_to_game_state(
     mut commands: Commands,
-    keyboard_input: Res<Input<KeyCode>>,
+    action_state: Res<ActionState>,
     app_state: Res<State<AppState>>
 
 ) {
-    if keyboard_input.just_pressed(KeyCode::G) {
-        if app_state.ne(&AppState::Game) {
-            commands.insert_resource(NextState(Some(AppState::Game)));
-            println!("Entered AppState:Game");
-        }
+    if action_state.just_pressed(Action::DebugGame) && app_state.ne(&AppState::Game) {
+        commands.insert_resource(NextState(Some(AppState::Game)));
+        println!("Entered AppState:Game");
     }
 }
 
 pub fn transition_to_main_menu_state(
     mut commands: Commands,
-    keyboard_input: Res<Input<KeyCode>>,
+    action_state: Res<ActionState>,
     app_state: Res<State<AppState>>
 
 ) {
-    if keyboard_input.just_pressed(KeyCode::M) {
-        if app_state.ne(&AppState::MainMenu) {
-            commands.insert_resource(NextState(Some(AppState::MainMenu)));
-            println!("Entered AppState:MainMenu");
-        }
-    }
-}
-
-
-pub fn exit_game(
-    keyboard_event: Res<Input<KeyCode>>,
-    mut app_exit_event_write: EventWriter<AppExit>,
-) {
-    if keyboard_event.just_pressed(KeyCode::Escape) {
-        app_exit_event_write.send(AppExit)
+    if action_state.just_pressed(Action::DebugMainMenu) && app_state.ne(&AppState::MainMenu) {
+        commands.insert_resource(NextState(Some(AppState::MainMenu)));
+        println!("Entered AppState:MainMenu");
     }
 }
 
 
-pub fn handle_game_over(mut game_over_event_reader: EventReader<GameOver>) {
+pub fn handle_game_over(mut commands: Commands, mut game_over_event_reader: EventReader<GameOver>) {
     for event in game_over_event_reader.read()  {
         println!("Your final score is {} !", event.score);
+        commands.insert_resource(NextState(Some(AppState::GameOver)));
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,25 @@
+use bevy::prelude::*;
+
+
+/// Root node of a menu that can be navigated with the keyboard or a gamepad.
+/// Its `Focusable` descendants are laid out row by row, `columns` per row.
+/// When several groups are on screen only the one with the highest `layer` takes input.
+#[derive(Component)]
+pub struct FocusGroup {
+    pub columns: usize,
+    pub layer: u8,
+}
+
+/// Position of a button within its `FocusGroup`.
+#[derive(Component)]
+pub struct Focusable {
+    pub index: usize,
+}
+
+/// The selected button of the active `FocusGroup`.
+#[derive(Component)]
+pub struct Focused;
+
+/// Activated by Escape or the gamepad's East button while its `FocusGroup` is active.
+#[derive(Component)]
+pub struct CancelButton;
@@ -0,0 +1,32 @@
+use bevy::prelude::*;
+
+pub mod components;
+pub mod styles;
+mod systems;
+
+use crate::events::ButtonActivated;
+
+use self::systems::*;
+
+/// `FocusGroup` layers, from full screens up to the settings overlay that opens on top of them.
+pub const SCREEN_LAYER: u8 = 0;
+pub const PANEL_LAYER: u8 = 1;
+pub const SETTINGS_LAYER: u8 = 2;
+
+pub struct UiPlugin;
+
+impl Plugin for UiPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ButtonActivated>()
+            .add_systems(
+                Update,
+                (
+                    focus_hovered_buttons,
+                    move_focus,
+                    activate_buttons,
+                    update_button_colors,
+                )
+                    .chain(),
+            );
+    }
+}
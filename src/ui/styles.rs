@@ -0,0 +1,86 @@
+use bevy::prelude::*;
+
+pub const NORMAL_BUTTON_COLOR: Color = Color::rgb(0.15, 0.15, 0.15);
+pub const FOCUSED_BUTTON_COLOR: Color = Color::rgb(0.25, 0.25, 0.4);
+pub const PRESSED_BUTTON_COLOR: Color = Color::rgb(0.35, 0.55, 0.35);
+pub const OVERLAY_COLOR: Color = Color::rgba(0.0, 0.0, 0.0, 0.6);
+
+pub fn screen_style() -> Style {
+    Style {
+        width: Val::Percent(100.0),
+        height: Val::Percent(100.0),
+        flex_direction: FlexDirection::Column,
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        row_gap: Val::Px(12.0),
+        ..default()
+    }
+}
+
+pub fn button_style() -> Style {
+    Style {
+        width: Val::Px(280.0),
+        height: Val::Px(56.0),
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        ..default()
+    }
+}
+
+/// Shorter rows for menus with too many entries to fit at full button height.
+pub fn list_button_style() -> Style {
+    Style {
+        width: Val::Px(360.0),
+        height: Val::Px(40.0),
+        ..button_style()
+    }
+}
+
+pub fn small_button_style() -> Style {
+    Style {
+        width: Val::Px(48.0),
+        height: Val::Px(48.0),
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        ..default()
+    }
+}
+
+pub fn title_text_style() -> TextStyle {
+    TextStyle {
+        font_size: 56.0,
+        color: Color::WHITE,
+        ..default()
+    }
+}
+
+pub fn body_text_style() -> TextStyle {
+    TextStyle {
+        font_size: 28.0,
+        color: Color::WHITE,
+        ..default()
+    }
+}
+
+/// For secondary lines, e.g. the run breakdown on the game over screen.
+pub fn detail_text_style() -> TextStyle {
+    TextStyle {
+        font_size: 20.0,
+        ..body_text_style()
+    }
+}
+
+pub fn button_text_style() -> TextStyle {
+    TextStyle {
+        font_size: 28.0,
+        color: Color::rgb(0.9, 0.9, 0.9),
+        ..default()
+    }
+}
+
+pub fn list_button_text_style() -> TextStyle {
+    TextStyle {
+        font_size: 22.0,
+        ..button_text_style()
+    }
+}
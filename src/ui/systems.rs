@@ -0,0 +1,199 @@
+use bevy::ecs::query::Has;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+
+use crate::controls::resources::{Action, ActionState};
+use crate::events::ButtonActivated;
+
+use super::components::*;
+use super::styles::*;
+
+
+/// The buttons that currently take keyboard and gamepad input.
+#[derive(SystemParam)]
+pub struct ActiveMenu<'w, 's> {
+    group_query: Query<'w, 's, (Entity, &'static FocusGroup)>,
+    children_query: Query<'w, 's, &'static Children>,
+    focusable_query: Query<'w, 's, (Entity, &'static Focusable, Has<Focused>)>,
+    cancel_query: Query<'w, 's, (), With<CancelButton>>,
+}
+
+impl<'w, 's> ActiveMenu<'w, 's> {
+    /// Buttons of the topmost `FocusGroup` sorted by index, together with its column count.
+    pub fn buttons(&self) -> Option<(usize, Vec<(Entity, bool)>)> {
+        let (group_entity, group) = self
+            .group_query
+            .iter()
+            .max_by_key(|(_, group)| group.layer)?;
+
+        let mut buttons: Vec<(usize, Entity, bool)> = self
+            .children_query
+            .iter_descendants(group_entity)
+            .filter_map(|entity| self.focusable_query.get(entity).ok())
+            .map(|(entity, focusable, focused)| (focusable.index, entity, focused))
+            .collect();
+        buttons.sort_by_key(|(index, _, _)| *index);
+
+        Some((
+            group.columns,
+            buttons
+                .into_iter()
+                .map(|(_, entity, focused)| (entity, focused))
+                .collect(),
+        ))
+    }
+
+    pub fn focused(&self) -> Option<Entity> {
+        let (_, buttons) = self.buttons()?;
+        buttons
+            .into_iter()
+            .find(|(_, focused)| *focused)
+            .map(|(entity, _)| entity)
+    }
+
+    pub fn cancel(&self) -> Option<Entity> {
+        let (_, buttons) = self.buttons()?;
+        buttons
+            .into_iter()
+            .map(|(entity, _)| entity)
+            .find(|entity| self.cancel_query.contains(*entity))
+    }
+}
+
+
+pub fn focus_hovered_buttons(
+    mut commands: Commands,
+    interaction_query: Query<(Entity, &Interaction), (Changed<Interaction>, With<Focusable>)>,
+    focused_query: Query<Entity, With<Focused>>,
+) {
+    for (entity, interaction) in interaction_query.iter() {
+        if *interaction == Interaction::Hovered {
+            for focused_entity in focused_query.iter() {
+                commands.entity(focused_entity).remove::<Focused>();
+            }
+            commands.entity(entity).insert(Focused);
+        }
+    }
+}
+
+pub fn move_focus(
+    mut commands: Commands,
+    action_state: Res<ActionState>,
+    active_menu: ActiveMenu,
+    focused_query: Query<Entity, With<Focused>>,
+) {
+    let Some((columns, buttons)) = active_menu.buttons() else {
+        return;
+    };
+    if buttons.is_empty() {
+        return;
+    }
+
+    // Buttons of menus hidden under the active one lose their focus.
+    for focused_entity in focused_query.iter() {
+        if !buttons.iter().any(|(entity, _)| *entity == focused_entity) {
+            commands.entity(focused_entity).remove::<Focused>();
+        }
+    }
+
+    let Some(current) = buttons.iter().position(|(_, focused)| *focused) else {
+        commands.entity(buttons[0].0).insert(Focused);
+        return;
+    };
+
+    let mut direction = IVec2::ZERO;
+    if action_state.just_pressed(Action::MenuUp) {
+        direction.y += 1;
+    }
+    if action_state.just_pressed(Action::MenuDown) {
+        direction.y -= 1;
+    }
+    if action_state.just_pressed(Action::MenuLeft) {
+        direction.x -= 1;
+    }
+    if action_state.just_pressed(Action::MenuRight) {
+        direction.x += 1;
+    }
+
+    let next = if action_state.just_pressed(Action::MenuNext) {
+        (current + 1) % buttons.len()
+    } else if direction != IVec2::ZERO {
+        step_in_grid(current, buttons.len(), columns, direction)
+    } else {
+        return;
+    };
+
+    if next != current {
+        commands.entity(buttons[current].0).remove::<Focused>();
+        commands.entity(buttons[next].0).insert(Focused);
+    }
+}
+
+pub fn activate_buttons(
+    action_state: Res<ActionState>,
+    active_menu: ActiveMenu,
+    interaction_query: Query<(Entity, &Interaction), (Changed<Interaction>, With<Button>)>,
+    mut button_activated_event_writer: EventWriter<ButtonActivated>,
+) {
+    for (entity, interaction) in interaction_query.iter() {
+        if *interaction == Interaction::Pressed {
+            button_activated_event_writer.send(ButtonActivated { entity });
+        }
+    }
+
+    if action_state.just_pressed(Action::Confirm) {
+        if let Some(entity) = active_menu.focused() {
+            button_activated_event_writer.send(ButtonActivated { entity });
+        }
+    }
+
+    if action_state.just_pressed(Action::Back) {
+        if let Some(entity) = active_menu.cancel() {
+            button_activated_event_writer.send(ButtonActivated { entity });
+        }
+    }
+}
+
+pub fn update_button_colors(
+    mut button_query: Query<(&Interaction, Has<Focused>, &mut BackgroundColor), With<Focusable>>,
+) {
+    for (interaction, focused, mut background_color) in button_query.iter_mut() {
+        let color = match interaction {
+            Interaction::Pressed => PRESSED_BUTTON_COLOR,
+            Interaction::Hovered => FOCUSED_BUTTON_COLOR,
+            Interaction::None if focused => FOCUSED_BUTTON_COLOR,
+            Interaction::None => NORMAL_BUTTON_COLOR,
+        };
+
+        if background_color.0 != color {
+            background_color.0 = color;
+        }
+    }
+}
+
+
+/// Moves `index` one cell in `direction` (+y is up) on a grid `columns` wide,
+/// wrapping around at the edges. The last row may be shorter than the others.
+fn step_in_grid(index: usize, len: usize, columns: usize, direction: IVec2) -> usize {
+    let columns = columns.clamp(1, len);
+    let rows = len.div_ceil(columns);
+    let mut row = index / columns;
+    let mut column = index % columns;
+
+    if direction.x != 0 {
+        let row_len = (len - row * columns).min(columns);
+        column = (column as i32 + direction.x).rem_euclid(row_len as i32) as usize;
+    }
+
+    if direction.y != 0 {
+        // Skip rows too short to have this column; the first row is always full.
+        loop {
+            row = (row as i32 - direction.y).rem_euclid(rows as i32) as usize;
+            if row * columns + column < len {
+                break;
+            }
+        }
+    }
+
+    row * columns + column
+}